@@ -1,18 +1,37 @@
 use proc_macro::TokenStream;
-use quote::{format_ident, quote, quote_spanned};
+use proc_macro2::Span;
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use std::collections::HashSet;
 use syn::{
-    parse::{Parse, ParseStream, Result},
+    ext::IdentExt,
+    parse::{Parse, ParseStream, Parser, Result},
     punctuated::Punctuated,
     spanned::Spanned,
-    Expr, Ident, ItemFn, Token,
+    token, AngleBracketedGenericArguments, Attribute, Expr, FnArg, Ident, ItemFn, Lit, LitInt,
+    LitStr, Meta, MetaNameValue, Pat, PatOr, Path, ReturnType, Token, Type, TypePath, Visibility,
 };
 
 /// An attribute which can be used in place of `#[test]` in order to run a single
 /// unit test with a list of different parameters.
 ///
 /// The macro checks that the length of each row in the parameter table matches
-/// the arity of the test function, and will fail to compile if this is not the
-/// case.
+/// the arity of the test function, and that no two columns share a name. It
+/// will fail to compile if this is not the case. The test function must be a
+/// free function, rather than a method which takes `self`.
+///
+/// A row which is the same as an earlier row is also rejected, as it is usually
+/// pasted twice by mistake. The `allow_duplicates` option permits repeated rows
+/// when they are intended.
+///
+/// Every problem which is found in the table is reported at once, rather than
+/// only the first, so that they can all be fixed together.
+///
+/// The names of the columns may be written as identifiers or as string
+/// literals, so `[ input , expected ]` and `[ "input" , "expected" ]` are the
+/// same header. They are not checked against the names of the parameters by
+/// default, as a column may be named after what its cells describe. The
+/// `strict` option requires them to be the same, except for parameters which
+/// are patterns rather than plain names.
 ///
 /// <br>
 ///
@@ -43,94 +62,8170 @@ use syn::{
 ///         assert_eq!(left + right, expected)
 ///     }
 ///
+///     /// case0: (1, 1), 2
 ///     #[test]
 ///     fn add_works_case0() {
 ///         add_works((1, 1), 2)
 ///     }
 ///
+///     /// case1: (2, 2), 4
 ///     #[test]
 ///     fn add_works_case1() {
 ///         add_works((2, 2), 4)
 ///     }
 /// }
 /// ```
-#[proc_macro_attribute]
-pub fn test_with_parameters(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let TableSyntax {
-        column_names,
-        test_inputs,
-    } = syn::parse_macro_input!(attr as TableSyntax);
-    let test_fn = syn::parse_macro_input!(item as ItemFn);
-
-    if column_names.len() != test_fn.sig.inputs.len() {
-        return (quote_spanned! {
-            column_names.span() =>
-            compile_error!("Number of parameters does not match the test function's arity.");
-        })
-        .into();
-    }
-
-    for args in test_inputs.iter() {
-        if args.len() != test_fn.sig.inputs.len() {
-            return (quote_spanned! {
-                args.span() =>
-                compile_error!("This case has the wrong number of arguments.");
-            })
-            .into();
-        }
-    }
-
-    let cases: Vec<_> = test_inputs
-        .into_iter()
-        .enumerate()
-        .map(|(idx, args)| {
-            let fn_name = format_ident!("{}_case{}", &test_fn.sig.ident, idx);
-            let call = &test_fn.sig.ident;
-            let args = args.iter();
-
-            let args_splat = quote! {
-                #(#args),*
-            };
-
-            quote! {
-                #[test]
-                fn #fn_name() {
-                    #call(#args_splat)
-                }
-            }
-        })
-        .collect();
-
-    (quote! {
-        #test_fn
-        #(#cases)*
-    })
-    .into()
-}
-
-struct TableSyntax {
-    column_names: Punctuated<Ident, Token![,]>,
-    test_inputs: Vec<Punctuated<Expr, Token![,]>>,
-}
-
-impl Parse for TableSyntax {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let names_input;
-        syn::bracketed!(names_input in input);
-
-        let column_names = names_input.parse_terminated(Ident::parse)?;
-
-        let mut test_inputs = vec![];
-        while !input.is_empty() {
-            let args_input;
-            syn::bracketed!(args_input in input);
-            let args = args_input.parse_terminated(Expr::parse)?;
-            test_inputs.push(args);
-        }
-
-        Ok(TableSyntax {
-            column_names,
-            test_inputs,
-        })
+///
+/// Each case is documented with the cells of the row it was generated from.
+/// The example is simplified: unless the `no_std` option is given, each case
+/// also declares a `CaseGuard`, which reports the case and where its row is if
+/// the test panics.
+///
+/// A cell may be any expression, including blocks and struct literals which
+/// span several lines. Only the commas between cells separate the columns, so
+/// the commas inside a cell's brackets, braces or generic arguments do not,
+/// nor do those in a macro call such as `vec![1, 2, 3]`. Each cell is parsed
+/// as a whole expression, so cells never need to be quoted or escaped.
+///
+/// Rows may also be written in braces as `column = cell` pairs, in any order.
+/// This is easier to read for wide tables, and each cell must name a column
+/// from the header. Marker columns are named with their `!`, such as `name!`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+///     { expected = 4, input = (2, 2) }
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// For tables of such rows, the brackets can be left out altogether by
+/// writing the rows in a `rows` block. Each row is a list of `column = cell`
+/// pairs, and the rows are separated by semicolons. The columns are those of
+/// the first row, in the order it gives them, so they cannot have types or
+/// defaults, and the later rows must give every one of them.
+///
+/// ```example
+/// #[test_with_parameters(
+///     rows {
+///         input = (1, 1) expected = 2;
+///         expected = 4   input = (2, 2);
+///     }
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Naming cases
+///
+/// By default each case is named after its index in the table. One column of
+/// the header may instead be marked with `name!`, in which case its cells are
+/// used to name the generated functions. Name cells may be identifiers or
+/// string literals. Identifiers are used as written, without any `r#` prefix,
+/// while string literals are lowercased and any runs of characters which are
+/// not valid in an identifier are replaced with an underscore. Case names must
+/// be unique within a table.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ name!     , input  , expected ]
+///     [ adds_zero , (0, 0) , 0        ]
+///     [ "1 + 1"   , (1, 1) , 2        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// This generates the cases `add_works_adds_zero` and `add_works_1_1`.
+///
+/// Otherwise, the `start` option sets the number to count cases from, and the
+/// `pad` option pads the numbers with zeroes so that the cases are listed in
+/// order. Either `pad = N` pads them to a width of `N`, or `pad` on its own
+/// pads them to the width of the largest number.
+///
+/// ```example
+/// #[test_with_parameters(
+///     start = 1, pad,
+///     [ input ]
+///     [ 1     ]
+///     // ...
+///     [ 12    ]
+/// )]
+/// fn is_positive(input: i32) {
+///     assert!(input > 0)
+/// }
+/// ```
+///
+/// This generates the cases `is_positive_case01` to `is_positive_case12`.
+///
+/// As inserting a row renumbers every row after it, the `naming = hash` option
+/// instead names each case after a hash of its cells, such as
+/// `add_works_case_1a2b3c4d`. These names only change when the row itself is
+/// changed. Rows whose cells are the same are told apart by a counter suffix.
+///
+/// The cases are generated in the order of their rows, unless the `sorted`
+/// option is given, in which case they are generated in the order of their
+/// names. This keeps the expansion of the macro stable when it is inspected
+/// with a tool such as `cargo expand`.
+///
+/// The cases are always named after the test function, but the function itself
+/// can be renamed with the `helper` option. This leaves its original name free
+/// to be used by another item. As the renamed function is only meant to be
+/// called by the cases, it is private and hidden from the documentation, even
+/// if the test function is `pub`. A visibility can be given before the new
+/// name to keep it visible, such as `helper = pub(crate) add_works_impl`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     helper = add_works_impl,
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// This generates the case `add_works_case0`, which calls `add_works_impl`.
+///
+/// The `call` option makes the cases call another function instead, such as
+/// an associated function or a function from another module, with the cells
+/// of each row. The test function is then only needed for its signature, so
+/// the `no_helper` option can be given to leave it out of the expansion, in
+/// which case its body is ignored.
+///
+/// ```example
+/// #[test_with_parameters(
+///     call = Calculator::check_add, no_helper,
+///     [ left , right , expected ]
+///     [ 1    , 1     , 2        ]
+/// )]
+/// fn add_works(left: usize, right: usize, expected: usize) {}
+/// ```
+///
+/// This generates the case `add_works_case0`, which calls
+/// `Calculator::check_add(1, 1, 2)`.
+///
+/// A row may also be tagged with a `#[tag(...)]` attribute, whose names are
+/// added to the name of its case, so that the tagged cases can be run with a
+/// filter such as `cargo test slow`. Several tags may be given, either in one
+/// attribute or in several.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input         , expected ]
+///     [ 1             , true     ]
+///     #[tag(slow, large)]
+///     [ 1_000_000_007 , true     ]
+/// )]
+/// fn is_prime_works(input: u64, expected: bool) {
+///     assert_eq!(is_prime(input), expected)
+/// }
+/// ```
+///
+/// This generates the cases `is_prime_works_case0` and
+/// `is_prime_works_slow_large_case1`.
+///
+/// Text can be added to the names of every case with the `prefix` and
+/// `suffix` options, such as `prefix = "fast_"`, which names the cases
+/// `add_works_fast_case0` and so on. The text must be made of characters which
+/// can be used in an identifier.
+///
+/// Tests which belong together can be given a `group`, such as `group =
+/// "addition"`, which is added to the names of their cases along with an `_`,
+/// and noted in their documentation. The group comes before any prefix and
+/// tags, so `group = "addition", prefix = "fast_"` names the cases
+/// `add_works_addition_fast_case0` and so on, and `cargo test addition` runs
+/// every case in the group.
+///
+/// For a naming scheme of its own, such as one expected by a custom test
+/// harness, the `name_template` option gives the whole name of each case. The
+/// placeholders `{fn}`, `{index}` and `{name_column}` are replaced with the
+/// name of the test function, the number of the case's row and the cells of
+/// its `name!` columns. The template must use `{index}` or `{name_column}` so
+/// that the cases have different names, and each name it gives must be an
+/// identifier.
+///
+/// ```example
+/// #[test_with_parameters(
+///     name_template = "{fn}__{index}",
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// This generates the case `add_works__0`. As the template gives the whole
+/// name, it cannot be used with tags or with the `group`, `prefix`, `suffix`,
+/// `naming` or `module` options.
+///
+/// <br>
+///
+/// # Typed columns
+///
+/// A column in the header may be given a type, like a function parameter. Each
+/// case then binds the cells of typed columns to variables of that type before
+/// calling the test function, so that a cell of the wrong type is reported at
+/// the cell itself. The type must be written in the same way as the type of the
+/// corresponding parameter.
+///
+/// If the parameter's type is generic, or an `impl Trait`, the column's type
+/// may instead be any type which the parameter accepts. This picks the type of
+/// the cells where it could not otherwise be inferred, such as the width of an
+/// integer literal like `5_000_000_000`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input: u64    , expected: i128 ]
+///     [ 5_000_000_000 , 5_000_000_000  ]
+/// )]
+/// fn widens<T: Into<i128>>(input: T, expected: i128) {
+///     assert_eq!(input.into(), expected)
+/// }
+/// ```
+///
+/// As each case is compiled separately, a cell of the wrong type is reported
+/// where it is passed to the test function. The `homogeneous` option also
+/// checks that the cells of each column have the same type as each other, so
+/// that a row with a string where the other rows have numbers is reported at
+/// that cell. Columns of generic or `impl Trait` parameters are not checked, as
+/// their cells may have different types. Nor are columns whose type cannot be
+/// written outside of the test function, such as one which mentions its
+/// lifetimes, columns with a `=>` function, and the columns checked by the
+/// `assert` option.
+///
+/// The cells of an `impl Trait` parameter may have a different concrete type in
+/// each row, as every case calls the test function separately. As such a type
+/// cannot be given to a variable, cells in an `impl Trait` column are passed to
+/// the test function without being bound first.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input: (usize, usize) , expected: usize ]
+///     [ (1, 1)                , 2               ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// A column may also be given a function with `=> function`, after its type
+/// and default cell if it has them. Each of its cells is passed through the
+/// function before the test function is called, so the cells can be written
+/// in a more compact form. A type given to such a column is the type which the
+/// function returns. As `:` already introduces a type, a function such as
+/// `PathBuf::from` cannot be written there.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ path => PathBuf::from , expected ]
+///     [ "a/b.txt"             , "b.txt"  ]
+///     [ "c.txt"               , "c.txt"  ]
+/// )]
+/// fn file_names(path: PathBuf, expected: &str) {
+///     assert_eq!(path.file_name().unwrap(), expected)
+/// }
+/// ```
+///
+/// As the most common such function is `Into::into`, a column can instead be
+/// marked with `into!`. Each of its cells is converted with `.into()` to the
+/// type of its parameter, so a `String` parameter can be given `&str` cells.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ into!(name) , expected ]
+///     [ "ab"        , 2        ]
+///     [ "abc"       , 3        ]
+/// )]
+/// fn name_lengths(name: String, expected: usize) {
+///     assert_eq!(name.len(), expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Default cells
+///
+/// A column in the header may be given a default cell with `= value`, after its
+/// type if it has one. Rows may then leave out the cells of trailing columns
+/// which have defaults, and named rows may leave out any cell which has one.
+/// As the test function is always called with every argument, a bracketed row
+/// must have a cell for every column up to the last one without a default, and
+/// may have cells for any number of the columns after it, in order.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input , expected , tolerance = 0.001 ]
+///     [ 0.5   , 0.25                         ]
+///     [ 0.1   , 0.01     , 0.01              ]
+/// )]
+/// fn square_works(input: f64, expected: f64, tolerance: f64) {
+///     assert!((input * input - expected).abs() < tolerance)
+/// }
+/// ```
+///
+/// A column marked with `?` after its name, such as `options?: Options`, is
+/// given `Default::default()` as its default cell.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input , options?: Options , expected ]
+///     { input = "a b", expected = ["a", "b"] }
+///     { input = "a,b", options = Options { comma: true }, expected = ["a", "b"] }
+/// )]
+/// fn splits(input: &str, options: Options, expected: [&str; 2]) {
+///     assert_eq!(split(input, options), expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Grouping cells into tuples
+///
+/// A column written as `tuple!(left, right)` takes a cell for each of its
+/// names, and passes them to a single parameter as a tuple. This saves writing
+/// the parentheses of the tuple in every row. Rows with such a column must be
+/// written in brackets, rather than as `column = cell` pairs.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ tuple!(left, right) , expected ]
+///     [ 1     , 1           , 2        ]
+///     [ 2     , 3           , 5        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// In the other direction, a column written as `spread!(left, right)` takes a
+/// tuple in each row, and passes its elements to a parameter for each of its
+/// names. The cells may be any expression of a tuple, such as a constant, but
+/// a cell written as a tuple must have an element for each name.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ spread!(left, right) , expected ]
+///     [ (1, 1)               , 2        ]
+///     [ (2, 3)               , 5        ]
+/// )]
+/// fn add_works(left: usize, right: usize, expected: usize) {
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Borrowing cells from statics
+///
+/// A column written as `by_ref!(name)` places each of its cells in a `static`,
+/// and passes a reference to it to the test function, whose parameter must
+/// then be a shared reference such as `&Config` or `&[u32]`. A large value is
+/// then built once, when the tests are compiled, rather than by each case.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ by_ref!(primes)       , input , expected ]
+///     [ [2, 3, 5, 7, 11, 13]  , 5     , true     ]
+///     [ [2, 3, 5, 7, 11, 13]  , 6     , false    ]
+/// )]
+/// fn is_listed(primes: &[u32], input: u32, expected: bool) {
+///     assert_eq!(primes.contains(&input), expected)
+/// }
+/// ```
+///
+/// As with any `static`, the cells must be constant expressions, and their type
+/// must be `Sync`. They cannot refer to the cells before them in their row.
+///
+/// <br>
+///
+/// # Cells which differ between platforms
+///
+/// A cell may use `cfg!` to give a value which depends on the platform, such as
+/// `if cfg!(windows) { "\r\n" } else { "\n" }`. More briefly, the cells of a
+/// column written as `platform!(name)` may be written as two values separated
+/// by `|`, where the value after the `|` is used on Windows and the value
+/// before it everywhere else. A cell without a `|` is used on every platform,
+/// so a value which is itself made with `|` must be wrapped in parentheses.
+///
+/// ```example
+/// #[test_with_parameters(
+///     assert = eq,
+///     [ input      , platform!(expected) ]
+///     [ &["a"]     , "a"                 ]
+///     [ &["a", "b"], "a\nb" | "a\r\nb"   ]
+/// )]
+/// fn join_lines(input: &[&str]) -> String {
+///     lines::join(input)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Referring to earlier cells
+///
+/// A cell may use the values of the cells before it in its row, by the names of
+/// their columns. A cell cannot use the value of a cell after it, and cells in
+/// a `single_test` table cannot refer to each other. The name of a later
+/// column is taken to refer to that column even if an item with the same name
+/// is in scope, so such an item must be written as a path, such as
+/// `self::offset()`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input , expected  ]
+///     [ 4     , input * 4 ]
+///     [ 5     , input * 4 ]
+/// )]
+/// fn quadruples(input: u32, expected: u32) {
+///     assert_eq!(input * 2 * 2, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Generic tests
+///
+/// Columns marked with `ty!` hold types rather than values, and are passed to
+/// the test function as type arguments, in the order they are written.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ ty! , value  ]
+///     [ u32 , 5      ]
+///     [ i64 , -3     ]
+/// )]
+/// fn round_trips<T: Serialize + DeserializeOwned>(value: T) {
+///     assert_eq!(deserialize::<T>(&serialize(&value)), value)
+/// }
+/// ```
+///
+/// This generates the calls `round_trips::<u32>(5)` and `round_trips::<i64>(-3)`.
+///
+/// When every case uses the same generic arguments, they can be given once
+/// with the `types` option instead, such as `types = <u32>`. If the table also
+/// has `ty!` or `const!` columns, their cells are used instead.
+///
+/// ```example
+/// #[test_with_parameters(
+///     types = <u32>,
+///     [ value ]
+///     [ 5     ]
+///     [ 7     ]
+/// )]
+/// fn round_trips<T: Serialize + DeserializeOwned>(value: T) {
+///     assert_eq!(deserialize::<T>(&serialize(&value)), value)
+/// }
+/// ```
+///
+/// Similarly, columns marked with `const!` hold the values of const generic
+/// parameters. Both kinds of column may be mixed, as long as they are written in
+/// the same order as the generic parameters of the test function.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ const! , data      ]
+///     [ 2      , [1, 2]    ]
+///     [ 3      , [1, 2, 3] ]
+/// )]
+/// fn buffer_fits<const N: usize>(data: [u8; N]) {
+///     assert!(Buffer::<N>::new().write(&data).is_ok())
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Sharing tables
+///
+/// A table can be declared once with [`parameter_table!`](macro@parameter_table)
+/// and then used by several tests with `use`.
+///
+/// ```example
+/// parameter_table! {
+///     additions =
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+/// }
+///
+/// #[test_with_parameters(use additions)]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Reading tables from CSV files
+///
+/// With the `csv` option, the table is read from a file instead, given relative
+/// to the root of the crate. The first line of the file is the header and each
+/// following line is a row. Lines are split into cells in the same way as rows
+/// written in the attribute, so commas inside brackets or string literals do not
+/// separate cells. Blank lines are ignored.
+///
+/// ```example
+/// // tests/data/add.csv:
+/// //
+/// // input  , expected
+/// // (1, 1) , 2
+/// // (2, 2) , 4
+///
+/// #[test_with_parameters(csv = "tests/data/add.csv")]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// Tables which are generated, such as by a build script, are best written to
+/// a file in this format. A procedural macro crate cannot export anything but
+/// macros, so the parser of this crate is not available to other code.
+///
+/// <br>
+///
+/// # Reading tables from arrays
+///
+/// Cases which are built by Rust code can instead be read from an array with
+/// the `from` option. As the length of the array is not known to the macro, it
+/// must also be given with the `count` option, which is checked when the crate
+/// is compiled, so the array must be a constant. Each case then calls the test
+/// function with the fields of a tuple in the array, or with the element itself
+/// if the function has only one parameter.
+///
+/// ```example
+/// const ADDITIONS: [((usize, usize), usize); 2] = [((1, 1), 2), ((2, 2), 4)];
+///
+/// #[test_with_parameters(from = ADDITIONS, count = 2)]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Crossing tables
+///
+/// Prefixing the table with `cross` allows it to be followed by further tables,
+/// each introduced by `with`. A case is then generated for every combination of
+/// rows from the tables, in which the cells of each row are passed in the order
+/// that the tables are written. Each case is named after the index of its row in
+/// each table, and any row attributes are combined.
+///
+/// ```example
+/// #[test_with_parameters(
+///     cross
+///     [ browser ]
+///     [ Browser::Chrome  ]
+///     [ Browser::Firefox ]
+///     with
+///     [ os ]
+///     [ Os::Linux ]
+///     [ Os::Mac   ]
+/// )]
+/// fn renders(browser: Browser, os: Os) {
+///     assert!(render(browser, os).is_ok())
+/// }
+/// ```
+///
+/// This generates the cases `renders_case0_0`, `renders_case0_1`,
+/// `renders_case1_0` and `renders_case1_1`. If the tables have `name!` columns,
+/// the names from each table are joined with underscores instead.
+///
+/// <br>
+///
+/// # Stacking tables
+///
+/// Several `#[test_with_parameters]` attributes can be applied to the same test
+/// function, and the cases of each are generated alongside each other. The rows
+/// of each table are numbered after the rows of the tables above it, so their
+/// cases have different names. A table given the `start` option is numbered
+/// from it instead, which must be after the numbers of the tables above. Cases
+/// named by a `name!` column or by `naming = hash` must still have different
+/// names across the tables.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+/// )]
+/// #[test_with_parameters(
+///     [ input   , expected ]
+///     [ (-1, 1) , 0        ]
+/// )]
+/// fn add_works(input: (isize, isize), expected: isize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// This generates the cases `add_works_case0` and `add_works_case1`. As the
+/// test function is removed by the `inline` and `no_helper` options, they can
+/// only be given to the last attribute. So can the `expose_names` and
+/// `expose_count` options, which then cover the cases of every attribute. The
+/// `module` option can only be given to one of the attributes, as can the
+/// `single_test` option outside of a module, since each would otherwise
+/// declare an item with the same name.
+///
+/// <br>
+///
+/// # Ranges
+///
+/// A column written as `range!(name)` holds ranges with integer literal bounds,
+/// such as `0..5` or `-1..=1`, and each row generates a case for every value in
+/// its range. The other cells of the row are the same in each of these cases.
+/// If a row has several ranges, they are zipped together and so must have the
+/// same length. Ranges can be combined instead by putting them in separate
+/// tables with `cross`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ range!(input) , expected ]
+///     [ 0..3          , false    ]
+///     [ 3..=4         , true     ]
+/// )]
+/// fn is_large(input: u32, expected: bool) {
+///     assert_eq!(input >= 3, expected)
+/// }
+/// ```
+///
+/// Each case is named after its row and the position of its value in the range,
+/// so this generates the cases `is_large_case0_0` to `is_large_case0_2`,
+/// `is_large_case1_0` and `is_large_case1_1`.
+///
+/// To test every combination of several ranges, a table can instead be written
+/// as a `matrix`: a header followed by a range for each of its columns. This
+/// generates a case for each combination of their values, named after the
+/// position of each value in its range.
+///
+/// ```example
+/// #[test_with_parameters(
+///     matrix [ a , b ] a in 0..3, b in 0..=1
+/// )]
+/// fn and_is_commutative(a: u8, b: u8) {
+///     assert_eq!(a & b, b & a)
+/// }
+/// ```
+///
+/// This generates the six cases `and_is_commutative_case0_0` to
+/// `and_is_commutative_case2_1`. The columns of a `matrix` may have types, but
+/// not markers or default cells.
+///
+/// <br>
+///
+/// # Property tests
+///
+/// With the `proptest` feature of this crate enabled, a column may be marked
+/// with `strategy!(name)`, in which case its cells are `proptest` strategies.
+/// Each case then runs the test function with many values generated by its
+/// strategies, using `proptest!`, while the other cells stay the same. This
+/// lets a table mix cases with fixed values and property-based cases. The
+/// crate being tested must depend on `proptest` itself. A type given to such a
+/// column, such as `strategy!(input: u32)`, is the type of the values which its
+/// strategies generate.
+///
+/// As the test function is called from inside `proptest!`, it cannot be
+/// `async` or return a value, and the `single_test` and `bench` options cannot
+/// be used.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ strategy!(input) , divisor ]
+///     [ 0..100u32        , 1       ]
+///     [ Just(12)         , 3       ]
+/// )]
+/// fn remainders_are_smaller(input: u32, divisor: u32) {
+///     assert!(input % divisor < divisor)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Nesting cases in a module
+///
+/// With the `module` option, the cases are generated inside a module named
+/// after the test function rather than alongside it, and the `_case` prefix is
+/// dropped from their names. The module imports everything from its parent, so
+/// the rows may refer to the same items as the test function.
+///
+/// ```example
+/// #[test_with_parameters(
+///     module,
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+///     [ (2, 2) , 4        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// This generates the cases `add_works::case0` and `add_works::case1`.
+///
+/// As a case in a module may be named after a keyword, such as `match`, those
+/// cases are given raw identifiers, like `r#match`. Names which cannot be used
+/// as identifiers even then, like `self` or names which start with a digit, are
+/// prefixed with `case_`.
+///
+/// The `mod_attrs` option applies attributes to the module, written without
+/// their `#[...]`, so that the whole table can be enabled by a feature with
+/// `mod_attrs = [cfg(feature = "integration")]`.
+///
+/// <br>
+///
+/// # Running every case in a single test
+///
+/// Generating a test for each row can slow down compilation for very large
+/// tables. With the `single_test` option, a single test named `{fn}_all` is
+/// generated instead, which calls the test function with each row in turn and
+/// reports the name of the case which failed. This compiles much faster, but
+/// the cases are no longer isolated from each other: the first failing case
+/// stops the test, and the cases cannot be run or ignored individually.
+///
+/// As the rows are stored in an array, the cells in each column must have the
+/// same type. Giving the column a type helps when they would otherwise differ,
+/// such as references to arrays of different lengths which should be passed as
+/// slices. Rows cannot have attributes, and `ty!` and `const!` columns cannot
+/// be used.
+///
+/// ```example
+/// #[test_with_parameters(
+///     single_test,
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+///     [ (2, 2) , 4        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// With `single_test = both`, the separate cases are generated as well, and
+/// the `{fn}_all` test is marked `#[ignore]`. A plain `cargo test` then runs
+/// each case in isolation, as in CI, while `cargo test {fn}_all -- --ignored`
+/// runs the whole table as one test, without starting a test for each case.
+/// The table must follow the rules of both kinds of test, and no case may be
+/// named `all`.
+///
+/// A table which generates more than 1000 tests is warned about at its header,
+/// suggesting the `single_test` option. The limit can be changed with the
+/// `max_cases` option, such as `max_cases = 5000`. As procedural macros cannot
+/// emit their own warnings on stable Rust, this is reported as the use of a
+/// deprecated item.
+///
+/// <br>
+///
+/// # Compiling only for tests
+///
+/// Test functions are usually declared in a `#[cfg(test)]` module, so that they
+/// are not compiled into the crate itself. The `cfg_test` option instead puts
+/// `#[cfg(test)]` on the test function and everything generated for it, which
+/// is useful for tests outside such a module. Using it inside one is harmless.
+///
+/// ```example
+/// #[test_with_parameters(
+///     cfg_test,
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// Similarly, the `if_cfg` option puts `#[cfg(feature = "...")]` on each of the
+/// generated cases, so that a table of slow cases is only compiled when a
+/// feature of the crate is enabled. The test function is always compiled.
+///
+/// ```example
+/// #[test_with_parameters(
+///     if_cfg = "extended",
+///     [ input   , expected ]
+///     [ 1 << 20 , 1 << 21  ]
+/// )]
+/// fn doubles(input: u64, expected: u64) {
+///     assert_eq!(input * 2, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Compiling without `std`
+///
+/// The generated tests only need `core`, except that a failing case reports
+/// the location of its row using `std`, and `#[timeout]` runs cases on another
+/// thread. For tests in a `#![no_std]` crate, the `no_std` option leaves out
+/// the report, and does not allow timeouts.
+///
+/// ```example
+/// #[test_with_parameters(
+///     no_std,
+///     [ input , expected ]
+///     [ 1     , 2        ]
+/// )]
+/// fn doubles(input: u32, expected: u32) {
+///     assert_eq!(input * 2, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Counting cases
+///
+/// The `expose_count` option declares a constant holding the number of cases
+/// generated for the test, named after the test function. Rows which are
+/// skipped or compiled out with `#[cfg(...)]` or the `if_cfg` option are not
+/// counted.
+///
+/// ```example
+/// #[test_with_parameters(
+///     expose_count,
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+///     [ (2, 2) , 4        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+///
+/// #[test]
+/// fn add_works_is_covered() {
+///     assert!(ADD_WORKS_CASE_COUNT >= 2)
+/// }
+/// ```
+///
+/// The `expose_names` option similarly declares a function, named after the
+/// test function with a `_cases` suffix, which returns the names of the cases
+/// in the order they are generated. With the example above, `add_works_cases()`
+/// would return `["add_works_case0", "add_works_case1"]`.
+///
+/// <br>
+///
+/// # Documenting the table
+///
+/// With the `doc_table` option, the table is added to the documentation of the
+/// test function as a Markdown table, with a row for each case. This keeps the
+/// examples in the documentation in step with the cases which are tested. With
+/// the example above, `add_works` would be documented with:
+///
+/// ```text
+/// | input | expected |
+/// | --- | --- |
+/// | `(1 , 1)` | `2` |
+/// | `(2 , 2)` | `4` |
+/// ```
+///
+/// The test function is only documented when it is kept, so this cannot be
+/// used with the `inline`, `no_helper` or `expr` options.
+///
+/// <br>
+///
+/// # Repeating cases
+///
+/// The `repeat` option runs each case the given number of times, which helps
+/// to catch failures which only happen occasionally. A case fails as soon as
+/// any of its runs fails, and the cells are evaluated afresh for each run.
+///
+/// ```example
+/// #[test_with_parameters(
+///     repeat = 100,
+///     [ threads ]
+///     [ 2       ]
+///     [ 8       ]
+/// )]
+/// fn counter_is_consistent(threads: usize) {
+///     assert_eq!(count_concurrently(threads), threads)
+/// }
+/// ```
+///
+/// A table must have at least one column, so a test without parameters cannot
+/// be repeated with empty rows such as `[ ] [ ] [ ]`. Such a test should use
+/// `#[test]` instead, with a loop if it should run more than once.
+///
+/// <br>
+///
+/// # Running cases one at a time
+///
+/// Tests are run in parallel by default, which is a problem for cases which
+/// share global state, such as environment variables. With the `serial`
+/// option, each case holds a lock for as long as it runs, so that no two cases
+/// of the test function run at the same time. The lock is shared by every
+/// stacked table which gives the option, and a case which panics does not stop
+/// the others from taking it.
+///
+/// ```example
+/// #[test_with_parameters(
+///     serial,
+///     [ value  ]
+///     [ "info" ]
+///     [ "warn" ]
+/// )]
+/// fn reads_log_level(value: &str) {
+///     std::env::set_var("LOG_LEVEL", value);
+///     assert_eq!(log_level(), value)
+/// }
+/// ```
+///
+/// This cannot be used with the `single_test` or `no_std` options.
+///
+/// <br>
+///
+/// # Inlining the test function
+///
+/// By default each case calls the test function, so a panic's location is
+/// inside the function rather than in the case. With the `inline` option, the
+/// body of the test function is instead copied into each case, after binding
+/// the cells to its parameters, and the function itself is not generated. This
+/// makes each case larger to compile, so it is best kept for small tests. The
+/// test function cannot be generic, and `single_test` cannot be used.
+///
+/// ```example
+/// #[test_with_parameters(
+///     inline,
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// This generates:
+///
+/// ```example
+/// #[test]
+/// fn add_works_case0() {
+///     let input: (usize, usize) = (1, 1);
+///     let expected: usize = 2;
+///     {
+///         let (left, right) = input;
+///         assert_eq!(left + right, expected)
+///     }
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Passing the case name
+///
+/// With the `case_name` option, the name of each case is passed to the test
+/// function as an extra first parameter of type `&str`, before any fixture. It
+/// is the name of the generated test without its module path, such as
+/// `add_works_case0`, which is useful for naming snapshots.
+///
+/// Similarly, the `expose_index` option passes the number of the row which
+/// each case was generated from as an extra `usize` parameter, after the name.
+/// This is the number used in the names of the cases, counting from `start`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     case_name,
+///     [ input  ]
+///     [ (1, 1) ]
+///     [ (2, 2) ]
+/// )]
+/// fn renders(case_name: &str, input: (usize, usize)) {
+///     insta::assert_snapshot!(case_name, render(input))
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Fixtures
+///
+/// The `setup` option takes an expression which is evaluated afresh for each
+/// case. Its value is passed to the test function as an extra first
+/// parameter, which has no column in the table. If that parameter is a
+/// reference, such as `&mut TestDb`, the fixture is lent to the test function
+/// instead, and is dropped at the end of the case. A `'static` reference, such
+/// as `&'static str`, is never lent, so its setup must give the reference
+/// itself, as in `setup = "fixture"`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     setup = { TestDb::new() },
+///     [ key , value ]
+///     [ "a" , 1     ]
+///     [ "b" , 2     ]
+/// )]
+/// fn stores_values(mut db: TestDb, key: &str, value: u32) {
+///     db.insert(key, value);
+///     assert_eq!(db.get(key), Some(value))
+/// }
+/// ```
+///
+/// The `with_context` option is a shorthand for a `setup` option which calls
+/// a function without arguments, so `with_context = TestDb::new` is the same
+/// as `setup = TestDb::new()`.
+///
+/// Fixture functions, such as those written for `rstest`, can be given to the
+/// `fixtures` option as a bracketed list. Each function is called without
+/// arguments for every case, and the results are passed to the test function
+/// in the same order, after the fixture from `setup`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     fixtures = [db, client],
+///     [ key , value ]
+///     [ "a" , 1     ]
+/// )]
+/// fn serves_values(db: TestDb, client: Client, key: &str, value: u32) {
+///     db.insert(key, value);
+///     assert_eq!(client.get(&db, key), Some(value))
+/// }
+/// ```
+///
+/// Similarly, the `teardown` option takes an expression which is evaluated at
+/// the end of each case, after the test function has returned. It is also
+/// evaluated if the case panics, as it is run when a guard is dropped.
+///
+/// ```example
+/// #[test_with_parameters(
+///     setup = create_dir(), teardown = remove_dir(),
+///     [ name    ]
+///     [ "a.txt" ]
+/// )]
+/// fn writes_files(dir: &Path, name: &str) {
+///     write_file(dir, name);
+///     assert!(dir.join(name).exists())
+/// }
+/// ```
+///
+/// In an async test, both expressions are evaluated inside each async case, so
+/// they can `.await` futures. A `teardown` which uses `.await` cannot be run
+/// by a guard, so it is evaluated once the test function has returned instead,
+/// and is skipped if the case panics.
+///
+/// ```example
+/// #[test_with_parameters(
+///     setup = { connect().await }, teardown = disconnect().await,
+///     [ key , value ]
+///     [ "a" , 1     ]
+/// )]
+/// async fn stores_values(db: TestDb, key: &str, value: u32) {
+///     db.insert(key, value).await;
+///     assert_eq!(db.get(key).await, Some(value))
+/// }
+/// ```
+///
+/// With the `tempfile` feature, the `tempdir` option creates a new temporary
+/// directory with the `tempfile` crate for each case, and passes its path to
+/// the test function as a `&Path` in place of a fixture. The directory is
+/// removed at the end of the case, even if the case panics. It cannot be used
+/// with the `setup` option.
+///
+/// ```example
+/// #[test_with_parameters(
+///     tempdir,
+///     [ name    ]
+///     [ "a.txt" ]
+/// )]
+/// fn writes_files(dir: &Path, name: &str) {
+///     write_file(dir, name);
+///     assert!(dir.join(name).exists())
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Returning `Result`
+///
+/// If the test function has a return type, such as `Result<(), E>`, then each
+/// generated case has the same return type and returns the result of calling
+/// the function. This means the function body can use `?`. Such cases cannot
+/// also be marked `#[should_panic]`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input ]
+///     [ "1"   ]
+///     [ "23"  ]
+/// )]
+/// fn parses(input: &str) -> Result<(), std::num::ParseIntError> {
+///     input.parse::<u32>()?;
+///     Ok(())
+/// }
+/// ```
+///
+/// The `setup` expression is evaluated inside each case, so it can use `?` in
+/// the same way, and a case whose setup fails returns its error without
+/// calling the test function. The `?` is not added for you, so an infallible
+/// setup is written as usual. In an async test, the setup is evaluated inside
+/// the async case, so it can await a future first. Each combination is
+/// written as:
+///
+/// | setup | sync test | async test |
+/// | --- | --- | --- |
+/// | infallible | `setup = TestDb::new()` | `setup = TestDb::connect().await` |
+/// | fallible | `setup = TestDb::open()?` | `setup = TestDb::connect().await?` |
+///
+/// Cases whose result is checked by the `assert` option or a `check!` column
+/// do not return it, so their setup cannot use `?`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     setup = TestDb::open("test.db")?,
+///     [ key , value ]
+///     [ "a" , 1     ]
+/// )]
+/// fn stores_values(db: TestDb, key: &str, value: u32) -> Result<(), DbError> {
+///     db.insert(key, value)?;
+///     assert_eq!(db.get(key)?, Some(value));
+///     Ok(())
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Choosing the test attribute
+///
+/// Each case is marked with `#[test]` by default. The `attr` option can be used
+/// to mark the cases with a different attribute instead, such as one provided
+/// by another test framework.
+///
+/// ```example
+/// #[test_with_parameters(
+///     attr = wasm_bindgen_test,
+///     [ input , expected ]
+///     [ 1     , 2        ]
+/// )]
+/// fn doubles(input: usize, expected: usize) {
+///     assert_eq!(double(input), expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Benchmarks
+///
+/// With the `bench` feature of this crate enabled, the `bench` option marks
+/// each case with `#[bench]` instead, and calls the test function inside
+/// `Bencher::iter`. The cells are evaluated on each iteration, so they are
+/// included in the measurement. As `#[bench]` is unstable, this needs a nightly
+/// compiler and `#![feature(test)]` and `extern crate test;` in the crate root.
+///
+/// The same table can then be used for both tests and benchmarks by sharing it
+/// with `parameter_table!`. Benchmarks cannot be `async`, run in a single test,
+/// repeated, or given a timeout.
+///
+/// ```example
+/// #[test_with_parameters(
+///     bench,
+///     [ input , expected ]
+///     [ 10    , 55       ]
+///     [ 20    , 6765     ]
+/// )]
+/// fn fibonacci_works(input: u64, expected: u64) {
+///     assert_eq!(fibonacci(input), expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # The `test-case` crate
+///
+/// With the `test_case` feature of this crate enabled, the `test_case` option
+/// keeps the test function as it is and marks it with one
+/// `#[test_case::test_case(..)]` attribute per row instead, so the cases are
+/// generated by the `test-case` crate, which must be a dependency of the crate
+/// being tested. Each case is named with its usual name, without the name of
+/// the test function.
+///
+/// Only plain cells and `name!` columns can be used with this option, and rows
+/// cannot have attributes. Nor can options which change how each case is
+/// generated, such as `teardown`, `attr` or `homogeneous`, as the cases are
+/// generated by `test-case` instead.
+///
+/// ```example
+/// #[test_with_parameters(
+///     test_case,
+///     [ input , expected ]
+///     [ 1     , 2        ]
+///     [ 2     , 4        ]
+/// )]
+/// fn doubles(input: usize, expected: usize) {
+///     assert_eq!(double(input), expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Async tests
+///
+/// If the test function is `async`, each generated case is also `async` and
+/// awaits the function. By default the cases are marked with `#[tokio::test]`,
+/// but the attribute can be chosen with the `runtime` option, which is an alias
+/// for `attr`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     runtime = async_std::test,
+///     [ input , expected ]
+///     [ 1     , 2        ]
+/// )]
+/// async fn doubles(input: usize, expected: usize) {
+///     assert_eq!(double(input).await, expected)
+/// }
+/// ```
+///
+/// The attribute may also be given arguments, which are applied to each case
+/// as they are written, such as to choose the flavour of the Tokio runtime.
+///
+/// ```example
+/// #[test_with_parameters(
+///     runtime = tokio::test(flavor = "multi_thread", worker_threads = 4),
+///     [ input , expected ]
+///     [ 1     , 2        ]
+/// )]
+/// async fn doubles_in_parallel(input: usize, expected: usize) {
+///     assert_eq!(double(input).await, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Matching the returned value
+///
+/// With `assert = matches`, the last column of the table holds a pattern rather
+/// than an argument. Each case calls the test function with the other columns,
+/// and asserts that the value it returns matches the pattern.
+///
+/// ```example
+/// #[test_with_parameters(
+///     assert = matches,
+///     [ input , expected        ]
+///     [ "1"   , Ok(1)           ]
+///     [ "-1"  , Ok(-9..=-1)     ]
+///     [ "one" , Err(_)          ]
+/// )]
+/// fn parse(input: &str) -> Result<i32, ParseIntError> {
+///     input.parse()
+/// }
+/// ```
+///
+/// Similarly, with `assert = eq`, the last column holds the value which the
+/// test function is expected to return, and each case asserts that the two are
+/// equal with `assert_eq!`. The column takes the type of the returned value, so
+/// it cannot be given a type of its own.
+///
+/// ```example
+/// #[test_with_parameters(
+///     assert = eq,
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+///     [ (2, 3) , 5        ]
+/// )]
+/// fn add(input: (usize, usize)) -> usize {
+///     input.0 + input.1
+/// }
+/// ```
+///
+/// If the test function returns a tuple, the `expected` option can be used to
+/// check each of its fields against a column of its own. The last `expected`
+/// columns are then checked against the fields in order.
+///
+/// ```example
+/// #[test_with_parameters(
+///     assert = eq, expected = 2,
+///     [ a , b , sum , product ]
+///     [ 2 , 3 , 5   , 6       ]
+/// )]
+/// fn sum_and_product(a: u32, b: u32) -> (u32, u32) {
+///     (a + b, a * b)
+/// }
+/// ```
+///
+/// With `assert = err`, the test function returns a `Result` whose error can
+/// be displayed, and the last column holds text which the message of the error
+/// must contain. A cell of `None` instead asserts that the test function
+/// returned `Ok`, while an empty string accepts any error.
+///
+/// ```example
+/// #[test_with_parameters(
+///     assert = err,
+///     [ input , expected_err    ]
+///     [ "1"   , None            ]
+///     [ "one" , "invalid digit" ]
+///     [ ""    , ""              ]
+/// )]
+/// fn parse(input: &str) -> Result<i32, ParseIntError> {
+///     input.parse()
+/// }
+/// ```
+///
+/// With `assert = no_panic`, there is no column to check. Each case calls the
+/// test function inside `std::panic::catch_unwind`, and if it panics, fails
+/// with a message which names the case and points at its row. This suits
+/// tables of inputs which should all be handled without panicking. The
+/// function's arguments are moved into a closure which must be `UnwindSafe`,
+/// so cells such as `&mut` references or `&RefCell<_>` can only be used if the
+/// test function takes them wrapped in `AssertUnwindSafe`. This cannot be used
+/// with `no_std` or async tests.
+///
+/// ```example
+/// #[test_with_parameters(
+///     assert = no_panic,
+///     [ input      ]
+///     [ ""         ]
+///     [ "\u{0}"    ]
+///     [ "((("      ]
+/// )]
+/// fn parse_never_panics(input: &str) -> Result<Expr, ParseError> {
+///     parse(input)
+/// }
+/// ```
+///
+/// When the returned value cannot be written as a single value or pattern, a
+/// column marked with `check!` can hold a closure instead. Each case calls the
+/// test function with the other columns, then calls the closure with the value
+/// it returned, so the closure can make any assertions about it. A `check!`
+/// column cannot be used with the `assert` option.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input   , check!                                 ]
+///     [ "a b"   , |words| assert_eq!(words, ["a", "b"])  ]
+///     [ "a b c" , |words| assert!(words.contains(&"c"))  ]
+/// )]
+/// fn split(input: &str) -> Vec<&str> {
+///     input.split_whitespace().collect()
+/// }
+/// ```
+///
+/// For tables which only check an expression, the `expr` option takes the
+/// expression in place of a test function. Each case binds the cells of its
+/// row to the names of their columns, then checks the expression against the
+/// last column, with `assert = eq` unless another `assert` option is given. The
+/// function which the attribute is placed on is only used to name the cases,
+/// so it takes no parameters and is left out of the expansion.
+///
+/// ```example
+/// #[test_with_parameters(
+///     expr = { a + b },
+///     [ a , b , expected ]
+///     [ 1 , 2 , 3        ]
+///     [ 2 , 2 , 4        ]
+/// )]
+/// fn add_works() {}
+/// ```
+///
+/// For differential testing, the `reference` option names a function which is
+/// known to be correct, such as a slower implementation of the test function.
+/// Each case calls both of them with the cells of its row, and asserts that
+/// they return the same value, so the table only needs columns for the inputs.
+/// The cells are evaluated once for each call, so they cannot use the values of
+/// earlier columns, and the reference function is not given any extra
+/// arguments, such as fixtures.
+///
+/// ```example
+/// #[test_with_parameters(
+///     reference = naive_fibonacci,
+///     [ n  ]
+///     [ 0  ]
+///     [ 20 ]
+/// )]
+/// fn fibonacci(n: u64) -> u64 {
+///     fast_fibonacci(n)
+/// }
+/// ```
+///
+/// If the test function is a `const fn`, the `const_check` option also calls
+/// it with each row in a constant, so that a failing case stops the crate from
+/// compiling as well as failing its test. The returned value is compared with
+/// `==` when `assert = eq` is given, which can only be done in a constant for
+/// primitive types such as integers, `bool` and `char`. Rows which are expected
+/// to panic or are ignored are only run as tests.
+///
+/// ```example
+/// #[test_with_parameters(
+///     assert = eq, const_check,
+///     [ input , expected ]
+///     [ 1     , 2        ]
+///     [ 2     , 4        ]
+/// )]
+/// const fn double(input: u32) -> u32 {
+///     input * 2
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Golden files
+///
+/// With the `golden` feature of this crate enabled, the `golden` option takes
+/// the path of a file, relative to the root of the crate, which records the
+/// value returned by the test function for each case. Each line of the file is
+/// the name of a case, a `:` and the `{:?}` representation of its value. Each
+/// case asserts that its value is the same as the one recorded for it.
+///
+/// Running the tests with the `UPDATE_GOLDEN` environment variable set records
+/// the value of each case instead, adding lines for new cases and replacing
+/// those which changed. The lines are kept sorted, so the file only changes
+/// where the values do. The cases take turns to write the file by holding a
+/// `.lock` file next to it, which is reported if it is left behind for more
+/// than ten seconds, such as by a test run which was killed.
+///
+/// ```example
+/// // tests/golden/render.txt:
+/// //
+/// // renders_case0: "<p>a</p>"
+/// // renders_case1: "<p><em>b</em></p>"
+///
+/// #[test_with_parameters(
+///     golden = "tests/golden/render.txt",
+///     [ input ]
+///     [ "a"   ]
+///     [ "*b*" ]
+/// )]
+/// fn renders(input: &str) -> String {
+///     render(input)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Test attributes
+///
+/// Any `#[ignore]`, `#[should_panic]` or `#[cfg(...)]` attributes on the test
+/// function are applied to each of the generated cases. `#[ignore]` and
+/// `#[should_panic]` are removed from the function itself, while `#[cfg(...)]`
+/// is kept so that the function is compiled under the same conditions as its
+/// cases. The same goes for a `#[cfg_attr(...)]` which applies `#[ignore]` or
+/// `#[should_panic]`, such as `#[cfg_attr(ci, ignore)]`.
+///
+/// Attributes may also be placed before an individual row, in which case they
+/// are applied only to the case generated from that row. This is useful for
+/// marking the rows which are expected to panic, or which only apply on some
+/// platforms. Lint attributes such as `#[allow(...)]` are applied in the same
+/// way, so a lint which is triggered by one row's cells can be allowed for that
+/// case alone. As `#[cfg(...)]` attributes on rows might remove every case, the
+/// test function is allowed to be unused when any row has one. A
+/// `#[cfg_attr(...)]` on a row is passed on to its case unchanged, so that
+/// `#[cfg_attr(ci, ignore)]` ignores the case only when `ci` is set.
+///
+/// As cases which are slow to run natively are much slower under Miri, the
+/// `miri = skip_slow` option adds `#[cfg_attr(miri, ignore)]` to each row
+/// tagged with `#[tag(slow)]`, so that `cargo miri test` leaves them out.
+///
+/// ```example
+/// #[test_with_parameters(
+///     miri = skip_slow,
+///     [ input         , expected ]
+///     [ 7             , true     ]
+///     #[tag(slow)]
+///     [ 1_000_000_007 , true     ]
+/// )]
+/// fn is_prime_works(input: u64, expected: bool) {
+///     assert_eq!(is_prime(input), expected)
+/// }
+/// ```
+///
+/// Parameters of the test function may also have `#[cfg(...)]` attributes. The
+/// table must still have a column for each of them, and their cells are removed
+/// along with the parameters, so the rows do not change when a feature is
+/// enabled. This cannot be used with `single_test`.
+///
+/// Instead of marking the rows which should panic, the `panic_flag` option can
+/// name a column of `true` or `false` cells. The cases of the rows with `true`
+/// are marked `#[should_panic]`. The column is not passed to the test function.
+///
+/// ```example
+/// #[test_with_parameters(
+///     panic_flag = overflows,
+///     [ input           , overflows ]
+///     [ (1, 1)          , false     ]
+///     [ (usize::MAX, 1) , true      ]
+/// )]
+/// fn add_works(input: (usize, usize)) {
+///     let (left, right) = input;
+///     let _ = left + right;
+/// }
+/// ```
+///
+/// A row may also be given a `#[timeout(ms)]` attribute, which runs its case on
+/// another thread and fails it if it takes longer than the given number of
+/// milliseconds. A thread which times out is left running in the background.
+/// As the value returned by the test function is sent back from that thread, it
+/// must be `Send`, and timeouts cannot be used on async tests.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input , expected ]
+///     [ 1     , 2        ]
+/// )]
+/// #[ignore]
+/// fn slow_test(input: usize, expected: usize) {
+///     assert_eq!(expensive(input), expected)
+/// }
+///
+/// #[test_with_parameters(
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+///     #[ignore]
+///     [ (2, 2) , 4        ]
+///     #[should_panic(expected = "overflow")]
+///     [ (usize::MAX, 1) , 0 ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// <br>
+///
+/// # Failing cases
+///
+/// When a case panics, it prints the location of the row it was generated from
+/// to the test's output, as the panic itself is usually reported from inside
+/// the test function:
+///
+/// ```text
+/// Case `add_works_case2` failed. Its row is at tests/add.rs:12.
+/// ```
+///
+/// Rows read from a CSV file are reported by their line in the file instead.
+///
+/// A row may be given notes with `#[note = "..."]` attributes, which are
+/// printed after the location of the row, so that a failing case can point to
+/// the issue it is a regression test for. This needs `std`.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input  , expected ]
+///     #[note = "regression test for #123"]
+///     [ (1, 1) , 2        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// ```text
+/// Case `add_works_case0` failed. Its row is at tests/add.rs:12.
+/// Note: regression test for #123
+/// ```
+///
+/// The `show_cells` option also adds the cells of the row to the message of the
+/// panic, after their column names. As this catches the panic and panics again,
+/// it cannot be used with async tests.
+///
+/// ```text
+/// assertion `left == right` failed
+///   left: 3
+///  right: 4
+/// input=(1, 2) expected=4
+/// ```
+///
+/// <br>
+///
+/// # Skipping rows
+///
+/// A row may be disabled without deleting it by prefixing it with `skip`. No
+/// case is generated for a skipped row, but it still counts towards the
+/// numbering of the cases after it, so their names do not change.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+///     skip [ (2, 2) , 5   ]
+///     [ (3, 3) , 6        ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+///
+/// This generates the cases `add_works_case0` and `add_works_case2`.
+///
+/// <br>
+///
+/// # Dividing long tables
+///
+/// The header may be repeated between the rows of a long table, to keep it
+/// readable. A row which is written in exactly the same way as the header is
+/// ignored.
+///
+/// ```example
+/// #[test_with_parameters(
+///     [ input  , expected ]
+///     [ (1, 1) , 2        ]
+///     [ (2, 2) , 4        ]
+///     // ...
+///     [ input  , expected ]
+///     [ (9, 9) , 18       ]
+/// )]
+/// fn add_works(input: (usize, usize), expected: usize) {
+///     let (left, right) = input;
+///     assert_eq!(left + right, expected)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn test_with_parameters(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generate(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates the cases for `#[test_with_parameters]`, or the errors which
+/// prevent them from being generated.
+fn generate(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream> {
+    if uses_shared_table(&attr) {
+        let SharedTableSyntax { options, table } = syn::parse2(attr)?;
+        return Ok(quote! {
+            #table! { (#options) #item }
+        });
+    }
+
+    let TableSyntax {
+        mut options,
+        header,
+        column_names,
+        test_inputs,
+    } = syn::parse2(attr)?;
+    let mut test_fn = match syn::parse2(item)? {
+        syn::Item::Fn(test_fn) => test_fn,
+        item => {
+            return Err(syn::Error::new_spanned(
+                item,
+                "`#[test_with_parameters]` can only be applied to functions.",
+            ))
+        }
+    };
+    let (column_names, test_inputs) = match (&options.from, options.count) {
+        (Some(from), Some(count)) => from_table(&options, &test_fn, from, count),
+        _ => (column_names, test_inputs),
+    };
+
+    if options.start.is_some_and(|start| start < options.offset) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!(
+                "The `start` option of this table must be at least {}, as the cases of the tables above it are numbered up to {}.",
+                options.offset,
+                options.offset - 1
+            ),
+        ));
+    }
+    let stacked = number_stacked_table(&options, &mut test_fn, &test_inputs)?;
+    let serial_lock =
+        (!stacked && (options.serial || options.serial_above)).then(|| serial_lock(&test_fn));
+
+    let case_attrs: Vec<_> = test_fn
+        .attrs
+        .iter()
+        .filter(|attr| is_case_attr(attr))
+        .cloned()
+        .collect();
+    test_fn.attrs.retain(|attr| !is_test_only_attr(attr));
+
+    let has_cfg_rows = test_inputs
+        .iter()
+        .flat_map(|row| row.attrs.iter())
+        .any(|attr| attr.path.is_ident("cfg"));
+    let has_cfg_module = options
+        .mod_attrs
+        .iter()
+        .flatten()
+        .any(|attr| attr.path().is_ident("cfg"));
+    if has_cfg_rows || has_cfg_module || options.if_cfg.is_some() {
+        test_fn.attrs.push(syn::parse_quote!(#[allow(dead_code)]));
+    }
+
+    if let Some(receiver) = test_fn.sig.receiver() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "Methods cannot be parameterised, as the generated tests have no `self` to call them on. Use a free function instead.",
+        ));
+    }
+
+    // Every problem with the options and the table is reported at once, so
+    // that they can all be fixed together.
+    let mut errors = Errors::default();
+
+    let implicit_count = options.implicit_count();
+    if options.bench {
+        errors.extend(conflicts(
+            &options,
+            "The `bench` option",
+            &[
+                (test_fn.sig.asyncness.is_some(), "an async test function"),
+                (options.single_test, "the `single_test` option"),
+                (options.repeat.is_some(), "the `repeat` option"),
+                (options.test_attr.is_some(), "the `attr` option"),
+            ],
+        ));
+    }
+    if options.show_cells {
+        errors.extend(conflicts(
+            &options,
+            "The `show_cells` option",
+            &[
+                (test_fn.sig.asyncness.is_some(), "an async test function"),
+                (options.single_test, "the `single_test` option"),
+                (options.no_std, "the `no_std` option"),
+            ],
+        ));
+    }
+    if options.test_case {
+        errors.extend(conflicts(
+            &options,
+            "The `test_case` option",
+            &[
+                (options.single_test, "the `single_test` option"),
+                (options.module, "the `module` option"),
+                (options.inline, "the `inline` option"),
+                (options.assert.is_some(), "the `assert` option"),
+                (options.call.is_some(), "the `call` option"),
+                (options.expr.is_some(), "the `expr` option"),
+                (
+                    options.serial || options.serial_above,
+                    "the `serial` option",
+                ),
+                (options.show_cells, "the `show_cells` option"),
+                (options.repeat.is_some(), "the `repeat` option"),
+                (options.teardown.is_some(), "the `teardown` option"),
+                (options.no_std, "the `no_std` option"),
+                (options.bench, "the `bench` option"),
+                (options.test_attr.is_some(), "the `attr` option"),
+                (options.helper.is_some(), "the `helper` option"),
+                (options.if_cfg.is_some(), "the `if_cfg` option"),
+                (options.group.is_some(), "the `group` option"),
+                (options.expose_count, "the `expose_count` option"),
+                (options.expose_names, "the `expose_names` option"),
+                (options.homogeneous, "the `homogeneous` option"),
+                (options.max_cases.is_some(), "the `max_cases` option"),
+                (
+                    implicit_count > 0,
+                    "an option which passes extra arguments to the test function",
+                ),
+            ],
+        ));
+    }
+    if options.serial {
+        errors.extend(conflicts(
+            &options,
+            "The `serial` option",
+            &[
+                (options.single_test, "the `single_test` option"),
+                (options.no_std, "the `no_std` option"),
+            ],
+        ));
+    }
+    if options.types.is_some() {
+        errors.extend(conflicts(
+            &options,
+            "The `types` option",
+            &[
+                (options.inline, "the `inline` option"),
+                (options.expr.is_some(), "the `expr` option"),
+                (options.test_case, "the `test_case` option"),
+            ],
+        ));
+    }
+    if options.tempdir {
+        errors.extend(conflicts(
+            &options,
+            "The `tempdir` option",
+            &[
+                (options.setup.is_some(), "the `setup` option"),
+                (options.no_std, "the `no_std` option"),
+            ],
+        ));
+    }
+    if options.inline {
+        if let Some(param) = test_fn.sig.generics.params.first() {
+            errors.push(syn::Error::new_spanned(
+                param,
+                "The `inline` option cannot be used with a generic test function.",
+            ));
+        }
+        errors.extend(conflicts(
+            &options,
+            "The `inline` option",
+            &[
+                (options.single_test, "the `single_test` option"),
+                (options.call.is_some(), "the `call` option"),
+            ],
+        ));
+    }
+    if options.mod_attrs.is_some() {
+        errors.extend(conflicts(
+            &options,
+            "The `mod_attrs` option",
+            &[(
+                !options.module,
+                "a test which does not use the `module` option",
+            )],
+        ));
+    }
+    // Each stacked attribute would declare the same module or test.
+    if options.module && options.module_above {
+        errors.push(syn::Error::new(
+            options.key("module").map_or_else(Span::call_site, Ident::span),
+            "The `module` option can only be given to one of the `#[test_with_parameters]` attributes on the test function, as each would declare a module with the same name.",
+        ));
+    }
+    if options.single_test && !options.module && options.single_test_above {
+        errors.push(syn::Error::new(
+            options.key("single_test").map_or_else(Span::call_site, Ident::span),
+            format!(
+                "The `single_test` option can only be given to one of the `#[test_with_parameters]` attributes on the test function, as each would declare a test named `{}`.",
+                combined_name(&options, &test_fn)
+            ),
+        ));
+    }
+    if options.expose_names {
+        errors.extend(conflicts(
+            &options,
+            "The `expose_names` option",
+            &[(options.single_test, "the `single_test` option")],
+        ));
+    }
+    if options.expr.is_some() {
+        errors.extend(conflicts(
+            &options,
+            "The `expr` option",
+            &[
+                (options.inline, "the `inline` option"),
+                (options.call.is_some(), "the `call` option"),
+                (options.helper.is_some(), "the `helper` option"),
+                (options.single_test, "the `single_test` option"),
+                (options.from.is_some(), "the `from` option"),
+                (
+                    implicit_count > 0,
+                    "an option which passes extra arguments to the test function",
+                ),
+            ],
+        ));
+        if let Some(input) = test_fn.sig.inputs.first() {
+            errors.push(syn::Error::new_spanned(
+                input,
+                "The test function is only used for its name when the `expr` option is given, so it cannot have parameters.",
+            ));
+        }
+    }
+    if let Some(Assertion::NoPanic) = options.assert {
+        errors.extend(conflicts(
+            &options,
+            "`assert = no_panic`",
+            &[
+                (
+                    test_fn.sig.asyncness.is_some(),
+                    "an async test function, as a panic cannot be caught across an `.await`",
+                ),
+                (options.single_test, "the `single_test` option"),
+                (options.bench, "the `bench` option"),
+            ],
+        ));
+    }
+    if options.golden.is_some() {
+        errors.extend(conflicts(
+            &options,
+            "The `golden` option",
+            &[
+                (options.assert.is_some(), "the `assert` option"),
+                (options.reference.is_some(), "the `reference` option"),
+                (options.single_test, "the `single_test` option"),
+                (options.test_case, "the `test_case` option"),
+                (options.bench, "the `bench` option"),
+                (options.no_std, "the `no_std` option"),
+            ],
+        ));
+        for column in column_names.iter() {
+            if let Column::Check(_) = column {
+                errors.push(syn::Error::new_spanned(
+                    column,
+                    "A `check!` column cannot be used with the `golden` option.",
+                ));
+            }
+        }
+    }
+    if options.reference.is_some() {
+        errors.extend(conflicts(
+            &options,
+            "The `reference` option",
+            &[
+                (options.assert.is_some(), "the `assert` option"),
+                (options.expr.is_some(), "the `expr` option"),
+                (options.single_test, "the `single_test` option"),
+                (options.test_case, "the `test_case` option"),
+                (options.bench, "the `bench` option"),
+            ],
+        ));
+        for column in column_names.iter() {
+            if let Column::Check(_)
+            | Column::Parameter(Parameter {
+                cells: Cells::Spreads | Cells::Strategies,
+                ..
+            }) = column
+            {
+                errors.push(syn::Error::new_spanned(
+                    column,
+                    "This column cannot be used with the `reference` option.",
+                ));
+            }
+        }
+        // The cells are evaluated again for the reference function, after the
+        // values of earlier columns have been passed to the test function.
+        for row in test_inputs.iter() {
+            for (idx, cell) in row.args.iter().enumerate() {
+                let earlier = column_names
+                    .iter()
+                    .take(idx)
+                    .find_map(|column| match column {
+                        Column::Parameter(Parameter { name, .. }) => {
+                            mention(cell, name).map(|span| (span, name))
+                        }
+                        _ => None,
+                    });
+                if let Some((span, name)) = earlier {
+                    errors.push(syn::Error::new(
+                        span,
+                        format!(
+                            "This cell uses the `{}` column, which may have been moved into the test function, so it cannot be used with the `reference` option.",
+                            name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    if options.const_check {
+        let conflict = conflicts(
+            &options,
+            "The `const_check` option",
+            &[
+                (
+                    matches!(
+                        options.assert,
+                        Some(Assertion::Matches | Assertion::Err | Assertion::NoPanic)
+                    ),
+                    "an assertion other than `assert = eq`",
+                ),
+                (options.expected.is_some(), "the `expected` option"),
+                (options.inline, "the `inline` option"),
+                (options.call.is_some(), "the `call` option"),
+                (options.expr.is_some(), "the `expr` option"),
+                (options.test_case, "the `test_case` option"),
+                (test_fn.sig.asyncness.is_some(), "an async test function"),
+                (
+                    implicit_count > 0,
+                    "an option which passes extra arguments to the test function",
+                ),
+            ],
+        );
+        // The columns are only checked once the test function can be called.
+        let conflicted = conflict.is_some();
+        errors.extend(conflict);
+        if test_fn.sig.constness.is_none() {
+            errors.push(syn::Error::new_spanned(
+                test_fn.sig.fn_token,
+                "The `const_check` option needs the test function to be a `const fn`, as it is called when the crate is compiled.",
+            ));
+        }
+        if let (ReturnType::Type(..), None) = (&test_fn.sig.output, &options.assert) {
+            errors.push(syn::Error::new_spanned(
+                &test_fn.sig.output,
+                "The `const_check` option needs `assert = eq` when the test function returns a value, so that the value is checked.",
+            ));
+        }
+        for column in column_names.iter().filter(|_| !conflicted) {
+            if let Column::Parameter(Parameter {
+                cells: Cells::Values | Cells::Platforms,
+                ..
+            })
+            | Column::Expected(_)
+            | Column::CaseName(_)
+            | Column::PanicFlag(_) = column
+            {
+                continue;
+            }
+            errors.push(syn::Error::new_spanned(
+                column,
+                "This column cannot be used with the `const_check` option.",
+            ));
+        }
+    }
+    if options.doc_table {
+        // The option documents the test function, which these leave out.
+        errors.extend(conflicts(
+            &options,
+            "The `doc_table` option",
+            &[
+                (
+                    options.inline,
+                    "the `inline` option, which leaves out the test function that it documents",
+                ),
+                (
+                    options.no_helper,
+                    "the `no_helper` option, which leaves out the test function that it documents",
+                ),
+                (
+                    options.expr.is_some(),
+                    "the `expr` option, which leaves out the test function that it documents",
+                ),
+            ],
+        ));
+    }
+    if options.no_helper {
+        errors.extend(conflicts(
+            &options,
+            "The `no_helper` option",
+            &[
+                (
+                    options.call.is_none(),
+                    "a test which has no `call` option to give the function which the cases call",
+                ),
+                (options.helper.is_some(), "the `helper` option"),
+            ],
+        ));
+    }
+    if let Some(template) = &options.name_template {
+        errors.extend(conflicts(
+            &options,
+            "The `name_template` option",
+            &[
+                (options.module, "the `module` option"),
+                (options.single_test, "the `single_test` option"),
+                (options.test_case, "the `test_case` option"),
+                (options.naming.is_some(), "the `naming` option"),
+                (options.group.is_some(), "the `group` option"),
+                (options.prefix.is_some(), "the `prefix` option"),
+                (options.suffix.is_some(), "the `suffix` option"),
+            ],
+        ));
+        let named = column_names
+            .iter()
+            .any(|column| matches!(column, Column::CaseName(_)));
+        if template.value().contains("{name_column}") && !named {
+            errors.push(syn::Error::new(
+                template.span(),
+                "The `{name_column}` placeholder needs a `name!` column.",
+            ));
+        }
+    }
+
+    let parameter_count = column_names
+        .iter()
+        .map(|column| match column {
+            Column::Parameter(parameter) => parameter.width(),
+            _ => 0,
+        })
+        .sum::<usize>()
+        + implicit_count;
+
+    if options.expr.is_none() && parameter_count != test_fn.sig.inputs.len() {
+        errors.push(syn::Error::new(
+            column_names.span(),
+            "Number of parameters does not match the test function's arity.",
+        ));
+    }
+
+    let parameters = column_names.iter().filter_map(|column| match column {
+        Column::Parameter(parameter) => Some(parameter),
+        _ => None,
+    });
+    let mut inputs = test_fn.sig.inputs.iter().skip(implicit_count);
+    for parameter in parameters {
+        // A `spread!` column names each of the parameters which it covers.
+        let names = match parameter.cells {
+            Cells::Spreads => parameter.elements.iter().collect(),
+            _ => vec![&parameter.name],
+        };
+        for (name, input) in names.into_iter().zip(inputs.by_ref()) {
+            // A `tuple!` column is named after its cells, not its parameter.
+            let strict = options.strict && parameter.cells != Cells::Tuples;
+            if let (true, FnArg::Typed(input)) = (strict, input) {
+                if let Pat::Ident(pat) = &*input.pat {
+                    if pat.ident != *name {
+                        let message = format!(
+                            "This column does not have the same name as the parameter, `{}`.",
+                            pat.ident
+                        );
+                        errors.push(syn::Error::new(name.span(), message));
+                    }
+                }
+            }
+            if parameter.cells == Cells::Statics && static_type(&test_fn, input).is_none() {
+                errors.push(syn::Error::new(
+                    name.span(),
+                    "A `by_ref!` column must be passed to a parameter whose type is a shared reference to a type which is not generic, such as `&Config`.",
+                ));
+            }
+            if let (Some(ty), FnArg::Typed(input)) = (&parameter.ty, input) {
+                if !is_generic(&test_fn, &input.ty)
+                    && ty.to_token_stream().to_string() != input.ty.to_token_stream().to_string()
+                {
+                    let message = format!(
+                        "This column's type does not match the type of the parameter, `{}`.",
+                        input.ty.to_token_stream()
+                    );
+                    errors.push(syn::Error::new(ty.span(), message));
+                }
+            }
+        }
+    }
+
+    let strategy_column = column_names.iter().find(|column| {
+        matches!(
+            column,
+            Column::Parameter(Parameter {
+                cells: Cells::Strategies,
+                ..
+            })
+        )
+    });
+    if let Some(column) = strategy_column {
+        let conflict = first_conflict(&[
+            (test_fn.sig.asyncness.is_some(), "an async test function"),
+            (
+                matches!(
+                    (&test_fn.sig.output, &options.assert),
+                    (ReturnType::Type(..), None)
+                ),
+                "a test function which returns a value",
+            ),
+            (options.single_test, "the `single_test` option"),
+            (options.bench, "the `bench` option"),
+        ]);
+        if let Some(conflict) = conflict {
+            errors.push(syn::Error::new_spanned(
+                column,
+                format!("`strategy!` columns cannot be used with {}.", conflict),
+            ));
+        }
+    }
+
+    let by_ref_column = column_names.iter().find(|column| {
+        matches!(
+            column,
+            Column::Parameter(Parameter {
+                cells: Cells::Statics,
+                ..
+            })
+        )
+    });
+    if let Some(column) = by_ref_column {
+        let conflict = first_conflict(&[
+            (options.single_test, "the `single_test` option"),
+            (options.test_case, "the `test_case` option"),
+            (options.expr.is_some(), "the `expr` option"),
+            (options.reference.is_some(), "the `reference` option"),
+        ]);
+        if let Some(conflict) = conflict {
+            errors.push(syn::Error::new_spanned(
+                column,
+                format!("`by_ref!` columns cannot be used with {}.", conflict),
+            ));
+        }
+    }
+
+    if test_inputs.is_empty() {
+        errors.push(syn::Error::new(
+            header.span,
+            "This table has no rows, so no tests would be generated.",
+        ));
+    }
+
+    let panic_flag_column = column_names
+        .iter()
+        .position(|column| matches!(column, Column::PanicFlag(_)));
+    match (&options.panic_flag, panic_flag_column) {
+        (Some(flag), None) => errors.push(syn::Error::new(
+            flag.span(),
+            format!(
+                "There is no column named `{}` for the `panic_flag` option.",
+                flag
+            ),
+        )),
+        (_, Some(column)) => {
+            let cells = test_inputs
+                .iter()
+                .filter_map(|row| row.args.iter().nth(column));
+            for cell in cells.filter(|cell| panic_flag(cell).is_none()) {
+                errors.push(syn::Error::new_spanned(
+                    cell,
+                    "The cells of the `panic_flag` column must be `true` or `false`, as they decide whether the case is marked `#[should_panic]`.",
+                ));
+            }
+        }
+        (None, None) => {}
+    }
+
+    let mut seen_columns = HashSet::new();
+    for column in column_names.iter() {
+        if let Column::Parameter(Parameter { name, .. }) = column {
+            if !seen_columns.insert(name.to_string()) {
+                errors.push(syn::Error::new(
+                    name.span(),
+                    "This column name is used by another column.",
+                ));
+            }
+        }
+    }
+
+    let name_columns: Vec<_> = column_names
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| matches!(column, Column::CaseName(_)))
+        .map(|(idx, _)| idx)
+        .collect();
+
+    let mut well_formed_rows = Vec::with_capacity(test_inputs.len());
+    for row in test_inputs.iter() {
+        match row_length_error(&column_names, row) {
+            Some(error) => errors.push(error),
+            None => {
+                if let Some(error) = forward_reference_error(&column_names, row) {
+                    errors.push(error);
+                }
+                if let Some(error) = spread_error(&column_names, row) {
+                    errors.push(error);
+                }
+                well_formed_rows.push(row)
+            }
+        }
+    }
+
+    if !options.allow_duplicates {
+        let mut seen_rows = HashSet::new();
+        for Row {
+            attrs,
+            bracket,
+            args,
+            ..
+        } in well_formed_rows.iter()
+        {
+            if !seen_rows.insert(quote!(#(#attrs)* #args).to_string()) {
+                let message = "This row is the same as another row. Use the `allow_duplicates` option if this is intended.";
+                errors.push(syn::Error::new(bracket.span, message));
+            }
+        }
+    }
+
+    let asyncness = &test_fn.sig.asyncness;
+    let test_attr = match options.test_attr.take() {
+        Some(test_attr) => test_attr,
+        None if asyncness.is_some() => syn::parse_quote!(tokio::test),
+        None if options.bench => syn::parse_quote!(bench),
+        None => syn::parse_quote!(test),
+    };
+    let awaited = asyncness.map(|_| quote!(.await));
+    let setup = setup_binding(&options, &test_fn);
+    let fixtures = fixture_bindings(&options);
+
+    let start = options.first_index();
+    let width = match options.pad {
+        Some(Some(width)) => width,
+        Some(None) => {
+            let largest = test_inputs
+                .iter()
+                .flat_map(|row| row.index.iter())
+                .max()
+                .map_or(0, |idx| idx + start);
+            largest.to_string().len()
+        }
+        None => 0,
+    };
+
+    let mut case_names = HashSet::new();
+    let mut stems = Vec::with_capacity(test_inputs.len());
+    // Any errors are reported before the stems are used, so the rows which
+    // have the wrong length can be left out.
+    for Row {
+        attrs, index, args, ..
+    } in well_formed_rows
+    {
+        let numbered = || {
+            let index: Vec<_> = index
+                .iter()
+                .map(|idx| format!("{:0width$}", idx + start, width = width))
+                .collect();
+            index.join("_")
+        };
+        let tags: Vec<_> = attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("tag"))
+            .cloned()
+            .collect();
+        let tagged = |stem| tagged_stem(&tags, stem);
+        // The tags are part of the name which must be unique, so they are added
+        // before it is checked.
+        let stem = if let Some(template) = &options.name_template {
+            for tag in &tags {
+                errors.push(syn::Error::new_spanned(
+                    tag,
+                    "Tags cannot be used with the `name_template` option, as the template gives the whole name of the case.",
+                ));
+            }
+            let names = match named(&name_columns, args) {
+                Ok(names) => names,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+            match templated_name(template, &test_fn, &numbered(), &names) {
+                Ok(name) => name,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            }
+        } else if let (true, Some(Naming::Hash)) = (name_columns.is_empty(), &options.naming) {
+            // Rows with the same cells are told apart by a counter.
+            let hash = format!("case_{:08x}", fnv1a(&quote!(#args).to_string()));
+            let hash = match tagged(hash) {
+                Ok(hash) => hash,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+            let mut stem = hash.clone();
+            let mut count = 0;
+            while case_names.contains(&stem) {
+                count += 1;
+                stem = format!("{}_{}", hash, count);
+            }
+            stem
+        } else {
+            let stem = if name_columns.is_empty() {
+                format!("case{}", numbered())
+            } else {
+                match named(&name_columns, args) {
+                    Ok(names) => names,
+                    Err(error) => {
+                        errors.push(error);
+                        continue;
+                    }
+                }
+            };
+            match tagged(stem) {
+                Ok(stem) => stem,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            }
+        };
+
+        if !case_names.insert(stem.clone()) {
+            let cell = &args[name_columns.first().copied().unwrap_or_default()];
+            errors.push(syn::Error::new(
+                cell.span(),
+                "This case name is used by another case.",
+            ));
+        }
+
+        stems.push(stem);
+    }
+
+    errors.finish()?;
+
+    if options.doc_table {
+        test_fn.attrs.extend(doc_table(&column_names, &test_inputs));
+    }
+
+    if options.test_case {
+        test_fn.attrs.extend(case_attrs);
+        return test_cases(&options, &test_fn, &column_names, &test_inputs, &stems);
+    }
+
+    let case_count: Vec<_> = options
+        .expose_count
+        .then(|| case_count(&options, &test_fn, &test_inputs))
+        .into_iter()
+        .chain(serial_lock)
+        .chain(from_length_check(&options))
+        .chain(case_limit_warning(&options, header.span, test_inputs.len()))
+        .chain(
+            options
+                .homogeneous
+                .then(|| homogeneous_columns(&options, &test_fn, &column_names, &test_inputs))
+                .into_iter()
+                .flatten(),
+        )
+        .chain(
+            options
+                .const_check
+                .then(|| const_checks(&options, &test_fn, &case_attrs, &column_names, &test_inputs))
+                .into_iter()
+                .flatten(),
+        )
+        .collect();
+
+    let mut combined = None;
+    if options.single_test {
+        // The combined test is ignored alongside the separate cases, so that
+        // each case only runs once by default.
+        let mut attrs = case_attrs.clone();
+        if options.keep_cases && !attrs.iter().any(|attr| applies_attr(attr, "ignore")) {
+            attrs.push(syn::parse_quote!(#[ignore]));
+        }
+        let case = single_test(
+            &options,
+            &test_fn,
+            &test_attr,
+            &attrs,
+            &column_names,
+            &test_inputs,
+            &stems,
+        )?;
+        if !options.keep_cases {
+            return Ok(expand(&options, &test_fn, &case_count, &[case]));
+        }
+        combined = Some(case);
+    }
+
+    let bencher = Ident::new("bencher", Span::mixed_site());
+    let bench_param = options
+        .bench
+        .then(|| quote!(#bencher: &mut ::test::Bencher));
+
+    // The value returned by the test function is checked by the case, rather
+    // than being returned from it.
+    let checked = options.assert.is_some()
+        || options.reference.is_some()
+        || options.golden.is_some()
+        || column_names
+            .iter()
+            .any(|column| matches!(column, Column::Check(_)));
+    let mut cases = Vec::with_capacity(test_inputs.len());
+    // The problems with each row are collected in the same way, so that every
+    // row's problems are reported together.
+    let mut errors = Errors::default();
+    for (row, stem) in test_inputs.iter().zip(stems) {
+        let Row { attrs, args, .. } = row.clone();
+        let (tags, attrs): (Vec<_>, Vec<_>) = attrs
+            .into_iter()
+            .partition(|attr| attr.path.is_ident("tag"));
+        let stem = options.affixed_stem(&stem);
+        let details = case_details(&options, &test_fn, row, &stem);
+        let doc = format!("{}: {}", stem, quote!(#args));
+        let group_doc = options.group.as_ref().map(|group| {
+            let doc = format!("Part of the `{}` group.", group);
+            quote! {
+                #[doc = ""]
+                #[doc = #doc]
+            }
+        });
+        let (notes, attrs): (Vec<_>, Vec<_>) = attrs
+            .into_iter()
+            .partition(|attr| attr.path.is_ident("note"));
+        let (timeouts, mut attrs): (Vec<_>, Vec<_>) = attrs
+            .into_iter()
+            .partition(|attr| attr.path.is_ident("timeout"));
+        let flagged = column_names.iter().zip(args.iter()).any(|(column, cell)| {
+            matches!(column, Column::PanicFlag(_)) && panic_flag(cell) == Some(true)
+        });
+        if flagged {
+            attrs.push(syn::parse_quote!(#[should_panic]));
+        }
+        if let (Some(Miri::SkipSlow), true) = (&options.miri, is_tagged(&tags, "slow")) {
+            attrs.push(syn::parse_quote!(#[cfg_attr(miri, ignore)]));
+        }
+        // The case is documented with the cells as they were written, before
+        // they are passed through the transforms of their columns.
+        let args: Punctuated<Expr, Token![,]> = column_names
+            .iter()
+            .zip(args)
+            .map(|(column, cell)| column.transformed(cell))
+            .collect();
+        let fn_name = if options.module {
+            module_case_ident(&stem)
+        } else {
+            format_ident!("{}", options.case_fn_name(&test_fn, &stem))
+        };
+        // Cases which are compiled out by `#[cfg(...)]` may not clash, so only
+        // the names of cases which are always compiled are compared.
+        let case_name = match options.module {
+            true => format!("{}::{}", test_fn.sig.ident, fn_name),
+            false => fn_name.to_string(),
+        };
+        let compiled =
+            options.if_cfg.is_none() && !attrs.iter().any(|attr| attr.path.is_ident("cfg"));
+        let clash = options
+            .cases_above
+            .iter()
+            .any(|(cfgs, above)| cfgs.is_empty() && above.value() == case_name);
+        if compiled && clash {
+            let cell = &row.args[name_columns.first().copied().unwrap_or_default()];
+            errors.push(syn::Error::new(
+                cell.span(),
+                "This case name is used by a case of another `#[test_with_parameters]` attribute on the test function.",
+            ));
+            continue;
+        }
+        if options.keep_cases && fn_name == combined_name(&options, &test_fn) {
+            errors.push(syn::Error::new(
+                row.bracket.span,
+                format!(
+                    "This case has the same name as the test which runs every case, `{}`.",
+                    fn_name
+                ),
+            ));
+            continue;
+        }
+
+        if !checked && !returns_unit(&test_fn.sig.output) {
+            let should_panic = case_attrs
+                .iter()
+                .chain(attrs.iter())
+                .find(|attr| applies_attr(attr, "should_panic"));
+            if let Some(should_panic) = should_panic {
+                errors.push(syn::Error::new_spanned(
+                    should_panic,
+                    "`#[should_panic]` cannot be used on a test which returns a `Result`.",
+                ));
+                continue;
+            }
+        }
+
+        let call = options.call_target(&test_fn);
+        let output = if checked || returns_unit(&test_fn.sig.output) {
+            None
+        } else {
+            Some(&test_fn.sig.output)
+        };
+
+        let mut bindings: Vec<_> = serial_guard(&options, &test_fn).into_iter().collect();
+        bindings.extend(
+            setup
+                .iter()
+                .chain(&fixtures)
+                .map(|(_, binding)| binding.clone()),
+        );
+        bindings.extend(teardown_guard(&options, &test_fn));
+        let mut checks = vec![];
+        let mut strategies = vec![];
+        let mut generic_args = vec![];
+        let mut call_args = details;
+        call_args.extend(
+            setup
+                .iter()
+                .chain(&fixtures)
+                .map(|(fixture, _)| fixture.clone()),
+        );
+        let mut reference_args = vec![];
+        for (idx, (column, arg)) in column_names.iter().zip(args.iter()).enumerate() {
+            // The reference function is given the cells themselves, as the
+            // test function may take ownership of its arguments.
+            if let Column::Parameter(_) = column {
+                let input = test_fn.sig.inputs.iter().nth(call_args.len());
+                let cfgs = input.map(cfg_attrs).unwrap_or_default();
+                reference_args.push(quote!(#(#cfgs)* #arg));
+            }
+            match column {
+                // A type given to the column is the type of the generated
+                // values, which are rebound with it.
+                Column::Parameter(Parameter {
+                    name,
+                    ty,
+                    cells: Cells::Strategies,
+                    ..
+                }) => {
+                    strategies.push(quote!(#name in #arg));
+                    if let Some(ty) = ty {
+                        bindings.push(quote! { let #name: #ty = #name; });
+                    }
+                    call_args.push(quote!(#name));
+                }
+                // The `expr` option refers to the columns by name, so every
+                // cell is bound.
+                Column::Parameter(parameter) if options.expr.is_some() => {
+                    bindings.push(expr_binding(parameter, arg));
+                }
+                // The cell is built once, when the program is compiled, and
+                // the test function borrows it.
+                Column::Parameter(Parameter {
+                    name,
+                    cells: Cells::Statics,
+                    ..
+                }) => {
+                    let input = test_fn.sig.inputs.iter().nth(call_args.len());
+                    let cfgs = input.map(cfg_attrs).unwrap_or_default();
+                    let ty = input.and_then(|input| static_type(&test_fn, input));
+                    let cell = format_ident!("{}_CELL", name.unraw().to_string().to_uppercase());
+                    bindings.push(quote! {
+                        #(#cfgs)*
+                        let #name: &'static #ty = {
+                            static #cell: &#ty = &#arg;
+                            #cell
+                        };
+                    });
+                    call_args.push(quote!(#name));
+                }
+                Column::Parameter(Parameter {
+                    cells: Cells::Spreads,
+                    elements,
+                    ..
+                }) => {
+                    bindings.push(quote! { let (#(#elements),*) = #arg; });
+                    call_args.extend(elements.iter().map(|element| quote!(#element)));
+                }
+                // An `impl Trait` type cannot be written in a `let`, so those
+                // cells are passed to the test function directly.
+                Column::Parameter(Parameter {
+                    name, ty: Some(ty), ..
+                }) if !matches!(**ty, Type::ImplTrait(_)) => {
+                    let input = test_fn.sig.inputs.iter().nth(call_args.len());
+                    let cfgs = input.map(cfg_attrs).unwrap_or_default();
+                    bindings.push(quote! { #(#cfgs)* let #name: #ty = #arg; });
+                    call_args.push(quote!(#name));
+                }
+                // Other cells are only bound to their column's name when a
+                // later cell refers to it, as a closure passed straight to the
+                // test function can have its parameter types inferred.
+                Column::Parameter(Parameter { name, .. })
+                    if args
+                        .iter()
+                        .skip(idx + 1)
+                        .any(|cell| mention(cell, name).is_some()) =>
+                {
+                    let input = test_fn.sig.inputs.iter().nth(call_args.len());
+                    let cfgs = input.map(cfg_attrs).unwrap_or_default();
+                    bindings.push(quote! { #(#cfgs)* let #name = #arg; });
+                    call_args.push(quote!(#name));
+                }
+                Column::Parameter(_) => call_args.push(quote!(#arg)),
+                Column::TypeArgument(_) => generic_args.push(quote!(#arg)),
+                // Const arguments other than literals and paths must be
+                // wrapped in a block.
+                Column::ConstArgument(_) => match arg {
+                    Expr::Lit(_) | Expr::Path(_) => generic_args.push(quote!(#arg)),
+                    _ => generic_args.push(quote!({ #arg })),
+                },
+                Column::Pattern(_)
+                | Column::Expected(_)
+                | Column::ErrorMessage(_)
+                | Column::Check(_) => checks.push((column, arg)),
+                Column::CaseName(_) | Column::PanicFlag(_) => {}
+            }
+        }
+
+        // The arguments of parameters with `#[cfg]` attributes are removed
+        // along with them.
+        let splat = test_fn.sig.inputs.iter().zip(call_args.iter());
+        let splat = splat.map(|(input, arg)| {
+            let cfgs = cfg_attrs(input);
+            quote!(#(#cfgs)* #arg)
+        });
+        let args_splat = quote! {
+            #(#splat),*
+        };
+        let turbofish = if !generic_args.is_empty() {
+            Some(quote!(::<#(#generic_args),*>))
+        } else {
+            options.turbofish()
+        };
+
+        let call = if let Some(expr) = &options.expr {
+            quote!(#expr)
+        } else if options.inline {
+            inline_call(&test_fn, &call_args)
+        } else {
+            quote! {
+                #call #turbofish(#args_splat)#awaited
+            }
+        };
+        let body = match checks.as_slice() {
+            // The panic is still reported by the panic hook, before the case
+            // fails with a message of its own.
+            [] if matches!(options.assert, Some(Assertion::NoPanic)) => {
+                let message = format!(
+                    "Case `{}` panicked, but `assert = no_panic` expects it not to.",
+                    fn_name
+                );
+                quote_spanned! {
+                    Span::mixed_site() =>
+                    if ::std::panic::catch_unwind(move || {
+                        #call;
+                    })
+                    .is_err()
+                    {
+                        panic!(#message)
+                    }
+                }
+            }
+            [] => match (&options.reference, &options.golden) {
+                (Some(reference), _) => quote! {
+                    assert_eq!(#call, #reference(#(#reference_args),*))
+                },
+                (None, Some(golden)) => {
+                    let key = if options.module {
+                        format!("{}::{}", test_fn.sig.ident, fn_name)
+                    } else {
+                        fn_name.to_string()
+                    };
+                    golden_check(golden, &key, call)
+                }
+                (None, None) => call,
+            },
+            [(column, arg)] => check_value(column, call, arg),
+            // Several expected columns are checked against the fields of the
+            // tuple returned by the test function.
+            checks => {
+                let result = Ident::new("result", Span::mixed_site());
+                let checks = checks.iter().enumerate().map(|(idx, (column, arg))| {
+                    let idx = syn::Index::from(idx);
+                    check_value(column, quote!(#result.#idx), arg)
+                });
+                quote! {
+                    let #result = #call;
+                    #(#checks;)*
+                }
+            }
+        };
+        let body = match awaited_teardown(&options, &test_fn) {
+            Some(teardown) => {
+                let result = Ident::new("result", Span::mixed_site());
+                quote! {
+                    #(#bindings)*
+                    let #result = { #body };
+                    #teardown
+                    #result
+                }
+            }
+            None => quote! {
+                #(#bindings)*
+                #body
+            },
+        };
+        // The values of `strategy!` columns are generated by `proptest`, which
+        // runs the rest of the case for each of them.
+        let body = if strategies.is_empty() {
+            body
+        } else {
+            quote! {
+                ::proptest::proptest!(|(#(#strategies),*)| {
+                    #body
+                });
+            }
+        };
+        let body = if options.bench {
+            bench_body(&bencher, output, body)
+        } else {
+            body
+        };
+        let body = match (options.repeat, &output) {
+            (None, _) => body,
+            (Some(repeat), Some(ReturnType::Type(..))) => quote! {
+                for _ in 0..#repeat {
+                    { #body }?;
+                }
+                Ok(())
+            },
+            (Some(repeat), _) => quote! {
+                for _ in 0..#repeat {
+                    #body
+                }
+            },
+        };
+        let body = if options.show_cells {
+            with_cells(&column_names, &args, output, body)
+        } else {
+            body
+        };
+        // Each case reports where its row is if it panics, as the panic itself
+        // is usually located in the test function. This needs `std`.
+        let source = match &row.source {
+            Some(source) => quote!(#source),
+            None => quote_spanned! {
+                row.bracket.span =>
+                ::std::concat!(::std::file!(), ":", ::std::line!())
+            },
+        };
+        let mut message = format!("Case `{}` failed. Its row is at {{}}.", fn_name);
+        for note in &notes {
+            match note_text(note) {
+                Ok(note) => {
+                    // The note is part of the format string, so its braces are
+                    // escaped.
+                    let note = note.replace('{', "{{").replace('}', "}}");
+                    message.push_str(&format!("\nNote: {}", note));
+                }
+                Err(error) => errors.push(error),
+            }
+        }
+        let body = if let (true, Some(note)) = (options.no_std, notes.first()) {
+            errors.push(syn::Error::new_spanned(
+                note,
+                "`#[note]` cannot be used with the `no_std` option.",
+            ));
+            continue;
+        } else if options.no_std {
+            body
+        } else {
+            quote_spanned! {
+                Span::mixed_site() =>
+                struct CaseGuard;
+
+                impl Drop for CaseGuard {
+                    fn drop(&mut self) {
+                        if ::std::thread::panicking() {
+                            ::std::eprintln!(#message, #source);
+                        }
+                    }
+                }
+
+                let _guard = CaseGuard;
+                #body
+            }
+        };
+        let body = match timeouts.as_slice() {
+            [] => Ok(body),
+            [timeout] if options.no_std => Err(syn::Error::new_spanned(
+                timeout,
+                "`#[timeout]` cannot be used with the `no_std` option.",
+            )),
+            [timeout] if options.bench => Err(syn::Error::new_spanned(
+                timeout,
+                "`#[timeout]` cannot be used with the `bench` option.",
+            )),
+            [timeout] if asyncness.is_none() => with_timeout(timeout, &fn_name, body),
+            [timeout] => Err(syn::Error::new_spanned(
+                timeout,
+                "`#[timeout]` cannot be used on an async test.",
+            )),
+            [_, timeout, ..] => Err(syn::Error::new_spanned(
+                timeout,
+                "A row may only have one `#[timeout]` attribute.",
+            )),
+        };
+        let body = match body {
+            Ok(body) => body,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+
+        let case = quote! {
+            #[doc = #doc]
+            #group_doc
+            #[#test_attr]
+            #(#case_attrs)*
+            #(#attrs)*
+            #asyncness fn #fn_name(#bench_param) #output {
+                #body
+            }
+        };
+        let cfgs: Vec<_> = attrs
+            .into_iter()
+            .filter(|attr| attr.path.is_ident("cfg"))
+            .collect();
+        cases.push((fn_name.to_string(), cfgs, case));
+    }
+    errors.finish()?;
+
+    if options.sorted {
+        cases.sort_by(|(left, ..), (right, ..)| left.cmp(right));
+    }
+    let names = case_name_entries(&options, &test_fn, &cases);
+    if stacked {
+        pass_on_case_names(&mut test_fn, &names);
+    }
+    let case_names = options
+        .expose_names
+        .then(|| case_name_list(&test_fn, &names));
+    let cases: Vec<_> = cases
+        .into_iter()
+        .map(|(.., case)| case)
+        .chain(combined)
+        .collect();
+    let declarations: Vec<_> = case_count.into_iter().chain(case_names).collect();
+    Ok(expand(&options, &test_fn, &declarations, &cases))
+}
+
+/// Places a `#[test_case]` attribute on the test function for each row, for the
+/// `test_case` option, so that the `test-case` crate generates the cases. The
+/// attributes of the test function are kept after them, so that they apply to
+/// every case.
+fn test_cases(
+    options: &Options,
+    test_fn: &ItemFn,
+    column_names: &Punctuated<Column, Token![,]>,
+    test_inputs: &[Row],
+    stems: &[String],
+) -> Result<proc_macro2::TokenStream> {
+    for column in column_names.iter() {
+        match column {
+            Column::Parameter(Parameter {
+                cells: Cells::Values | Cells::Ranges | Cells::Tuples | Cells::Platforms,
+                ..
+            })
+            | Column::CaseName(_) => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    column,
+                    "This column cannot be used with the `test_case` option.",
+                ))
+            }
+        }
+    }
+    if let Some(attr) = test_inputs.iter().flat_map(|row| row.attrs.iter()).next() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "Rows cannot have attributes when using the `test_case` option.",
+        ));
+    }
+
+    let attrs = test_inputs.iter().zip(stems).map(|(row, stem)| {
+        let cells = column_names
+            .iter()
+            .zip(row.args.iter())
+            .filter(|(column, _)| matches!(column, Column::Parameter(_)))
+            .map(|(column, cell)| column.transformed(cell.clone()));
+        let name = options.affixed_stem(stem);
+        quote! {
+            #[::test_case::test_case(#(#cells),* ; #name)]
+        }
+    });
+    let cfg = options.cfg_test.then(|| quote!(#[cfg(test)]));
+    let from_length_check = from_length_check(options).map(|check| quote!(#cfg #check));
+
+    Ok(quote! {
+        #from_length_check
+        #cfg
+        #(#attrs)*
+        #test_fn
+    })
+}
+
+/// Checks each case when the crate is compiled, for the `const_check` option,
+/// by calling the test function in a constant. Cases which are expected to
+/// panic, or which are ignored, are left out.
+fn const_checks(
+    options: &Options,
+    test_fn: &ItemFn,
+    case_attrs: &[Attribute],
+    column_names: &Punctuated<Column, Token![,]>,
+    test_inputs: &[Row],
+) -> Vec<proc_macro2::TokenStream> {
+    let skipped = |attrs: &[Attribute]| {
+        attrs
+            .iter()
+            .any(|attr| applies_attr(attr, "should_panic") || applies_attr(attr, "ignore"))
+    };
+    if skipped(case_attrs) {
+        return vec![];
+    }
+
+    let flagged = |row: &Row| {
+        column_names
+            .iter()
+            .zip(row.args.iter())
+            .any(|(column, cell)| {
+                matches!(column, Column::PanicFlag(_)) && panic_flag(cell) == Some(true)
+            })
+    };
+
+    let helper = options.helper_name(test_fn);
+    let turbofish = options.turbofish();
+    let mut checks = vec![];
+    for row in test_inputs
+        .iter()
+        .filter(|row| !skipped(&row.attrs) && !flagged(row))
+    {
+        let mut inputs = test_fn.sig.inputs.iter();
+        let mut bindings = vec![];
+        let mut args = vec![];
+        let mut expected = None;
+        for (column, cell) in column_names.iter().zip(row.args.iter()) {
+            let cell = column.transformed(cell.clone());
+            match column {
+                Column::Parameter(Parameter { name, ty, .. }) => {
+                    let cfgs = inputs.next().map(cfg_attrs).unwrap_or_default();
+                    let ty = ty
+                        .as_ref()
+                        .filter(|ty| !matches!(***ty, Type::ImplTrait(_)))
+                        .map(|ty| quote!(: #ty));
+                    bindings.push(quote! { #(#cfgs)* let #name #ty = #cell; });
+                    args.push(quote!(#(#cfgs)* #name));
+                }
+                Column::Expected(name) => {
+                    bindings.push(quote! { let #name = #cell; });
+                    expected = Some(name);
+                }
+                _ => {}
+            }
+        }
+
+        let call = quote!(#helper #turbofish(#(#args),*));
+        let check = match expected {
+            Some(expected) => quote!(assert!(#call == #expected)),
+            None => call,
+        };
+        let cfgs = row.attrs.iter().filter(|attr| attr.path.is_ident("cfg"));
+        checks.push(quote! {
+            #(#cfgs)*
+            const _: () = {
+                #(#bindings)*
+                #check;
+            };
+        });
+    }
+    checks
+}
+
+/// Documents the test function with its table as a Markdown table, for the
+/// `doc_table` option, with a row for each case.
+fn doc_table(column_names: &Punctuated<Column, Token![,]>, test_inputs: &[Row]) -> Vec<Attribute> {
+    // Pipes are escaped even inside code spans, as they would otherwise end
+    // the cell of the Markdown table.
+    let line = |cells: Vec<String>| {
+        let cells: Vec<_> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let mut lines = vec![
+        String::new(),
+        line(column_names.iter().map(Column::label).collect()),
+        line(column_names.iter().map(|_| "---".to_string()).collect()),
+    ];
+    lines.extend(test_inputs.iter().map(|row| {
+        line(
+            row.args
+                .iter()
+                .map(|cell| format!("`{}`", cell.to_token_stream()))
+                .collect(),
+        )
+    }));
+    lines
+        .into_iter()
+        .map(|line| syn::parse_quote!(#[doc = #line]))
+        .collect()
+}
+
+/// The names of the generated cases, after those of the attributes stacked
+/// above this one, each with the `#[cfg]` attributes it is compiled under. In a
+/// module generated by the `module` option, the names start with the module's
+/// name.
+fn case_name_entries(
+    options: &Options,
+    test_fn: &ItemFn,
+    cases: &[(String, Vec<Attribute>, proc_macro2::TokenStream)],
+) -> Vec<proc_macro2::TokenStream> {
+    let feature = options
+        .if_cfg
+        .as_ref()
+        .map(|feature| quote!(#[cfg(feature = #feature)]));
+    let above = options
+        .cases_above
+        .iter()
+        .map(|(cfgs, case_name)| quote! { #(#cfgs)* #case_name });
+    let names = cases.iter().map(|(case_name, cfgs, _)| {
+        let case_name = match options.module {
+            true => format!("{}::{}", test_fn.sig.ident, case_name),
+            false => case_name.clone(),
+        };
+        quote! { #feature #(#cfgs)* #case_name }
+    });
+    above.chain(names).collect()
+}
+
+/// Declares a function returning the names of the generated cases, for the
+/// `expose_names` option. Cases which are not compiled are left out.
+fn case_name_list(
+    test_fn: &ItemFn,
+    names: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let name = format_ident!("{}_cases", test_fn.sig.ident);
+    quote! {
+        #[allow(dead_code)]
+        fn #name() -> &'static [&'static str] {
+            &[#(#names),*]
+        }
+    }
+}
+
+/// Passes the names of the cases on to the next `#[test_with_parameters]`
+/// attribute on the test function, so that the last attribute can declare the
+/// names and number of the cases of every attribute.
+fn pass_on_case_names(test_fn: &mut ItemFn, names: &[proc_macro2::TokenStream]) {
+    if let Some(stacked) = stacked_attr(test_fn) {
+        let table = syn::parse2::<proc_macro2::Group>(stacked.tokens.clone())
+            .map(|group| group.stream())
+            .unwrap_or_default();
+        let passed_on = passed_on(quote!(cases = [#(#names),*]));
+        stacked.tokens = quote!((#passed_on, #table));
+    }
+}
+
+/// Emits the test function alongside its generated cases.
+fn expand(
+    options: &Options,
+    test_fn: &ItemFn,
+    declarations: &[proc_macro2::TokenStream],
+    cases: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let mut items = vec![];
+    if !options.inline && !options.no_helper && options.expr.is_none() {
+        let mut helper = test_fn.clone();
+        // A renamed helper is only called by the cases, so it is hidden from
+        // the crate's API unless it is given a visibility of its own.
+        if let Some((vis, name)) = &options.helper {
+            helper.vis = vis.clone();
+            helper.sig.ident = name.clone();
+            helper.attrs.push(syn::parse_quote!(#[doc(hidden)]));
+        }
+        items.push(helper.into_token_stream());
+    }
+    items.extend(declarations.iter().cloned());
+    items.extend(options.csv.iter().map(|path| {
+        let path = resolve_path(path).to_string_lossy().into_owned();
+        quote! {
+            const _: &[u8] = include_bytes!(#path);
+        }
+    }));
+
+    let feature = options
+        .if_cfg
+        .as_ref()
+        .map(|feature| quote!(#[cfg(feature = #feature)]));
+    let cases = cases.iter().map(|case| quote!(#feature #case));
+    if options.module {
+        let mod_name = &test_fn.sig.ident;
+        let mod_attrs = options.mod_attrs.iter().flatten();
+        items.push(quote! {
+            #(#[#mod_attrs])*
+            mod #mod_name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #(#cases)*
+            }
+        });
+    } else {
+        items.extend(cases);
+    }
+
+    let cfg = options.cfg_test.then(|| quote!(#[cfg(test)]));
+    let items = items.iter().map(|item| quote!(#cfg #item));
+    quote! {
+        #(#items)*
+    }
+}
+
+/// Asserts that a value returned by the test function matches the cell of a
+/// row in a column marked by the `assert` option.
+fn check_value(
+    column: &Column,
+    value: proc_macro2::TokenStream,
+    cell: &Expr,
+) -> proc_macro2::TokenStream {
+    match column {
+        // Patterns such as `Some(_)` are checked in the same way as any other.
+        Column::Pattern(_) => quote! {
+            #[allow(clippy::redundant_pattern_matching)]
+            {
+                assert!(matches!(#value, #cell))
+            }
+        },
+        // A `None` cell checks that there was no error.
+        Column::ErrorMessage(_) if is_none(cell) => quote_spanned! {
+            Span::mixed_site() =>
+            if let ::std::result::Result::Err(error) = #value {
+                panic!("Expected `Ok`, but the test function returned the error `{}`.", error)
+            }
+        },
+        Column::ErrorMessage(_) => quote_spanned! {
+            Span::mixed_site() =>
+            {
+                let expected = #cell;
+                match #value {
+                    ::std::result::Result::Ok(_) => panic!(
+                        "Expected an error containing {:?}, but the test function returned `Ok`.",
+                        expected
+                    ),
+                    ::std::result::Result::Err(error) => {
+                        let message = error.to_string();
+                        assert!(
+                            message.contains(&*expected),
+                            "Expected an error containing {:?}, but the error was `{}`.",
+                            expected,
+                            message
+                        )
+                    }
+                }
+            }
+        },
+        // The closure is passed to a function so that the type of its
+        // parameter is known before its body is checked.
+        Column::Check(_) => quote_spanned! {
+            Span::mixed_site() =>
+            {
+                fn check<T>(value: T, check: impl FnOnce(T)) {
+                    check(value)
+                }
+                check(#value, #cell)
+            }
+        },
+        _ => quote! {
+            assert_eq!(#value, #cell)
+        },
+    }
+}
+
+/// Whether the type of a parameter is chosen by the caller, as it is either an
+/// `impl Trait` or one of the test function's type parameters. A typed column
+/// may then give the concrete type of its cells.
+fn is_generic(test_fn: &ItemFn, ty: &Type) -> bool {
+    match ty {
+        Type::ImplTrait(_) => true,
+        Type::Path(TypePath { qself: None, path }) => path.get_ident().is_some_and(|ident| {
+            test_fn
+                .sig
+                .generics
+                .type_params()
+                .any(|param| param.ident == *ident)
+        }),
+        _ => false,
+    }
+}
+
+/// Whether a type can be written outside of the test function, as it does not
+/// mention the function's generic parameters, its named lifetimes, `impl Trait`
+/// or `Self`.
+fn is_nameable(test_fn: &ItemFn, ty: &Type) -> bool {
+    fn check(tokens: proc_macro2::TokenStream, test_fn: &ItemFn) -> bool {
+        let mut lifetime = false;
+        tokens.into_iter().all(|token| {
+            let after_quote = std::mem::take(&mut lifetime);
+            match token {
+                proc_macro2::TokenTree::Group(group) => check(group.stream(), test_fn),
+                proc_macro2::TokenTree::Punct(punct) => {
+                    lifetime = punct.as_char() == '\'';
+                    true
+                }
+                proc_macro2::TokenTree::Ident(ident) if after_quote => {
+                    ident == "static" || ident == "_"
+                }
+                proc_macro2::TokenTree::Ident(ident) => {
+                    ident != "impl"
+                        && ident != "Self"
+                        && !test_fn
+                            .sig
+                            .generics
+                            .type_params()
+                            .any(|param| param.ident == ident)
+                        && !test_fn
+                            .sig
+                            .generics
+                            .const_params()
+                            .any(|param| param.ident == ident)
+                }
+                proc_macro2::TokenTree::Literal(_) => true,
+            }
+        })
+    }
+    check(ty.to_token_stream(), test_fn)
+}
+
+/// The type of the `static` which holds the cells of a `by_ref!` column, if the
+/// column's parameter is a shared reference to a type which is not generic.
+fn static_type<'a>(test_fn: &ItemFn, input: &'a FnArg) -> Option<&'a Type> {
+    match input {
+        FnArg::Typed(input) => match &*input.ty {
+            Type::Reference(reference)
+                if reference.mutability.is_none() && !is_generic(test_fn, &reference.elem) =>
+            {
+                Some(&reference.elem)
+            }
+            _ => None,
+        },
+        FnArg::Receiver(_) => None,
+    }
+}
+
+/// Binds a cell to the name of its column, for the `expr` option. The cells of
+/// a `tuple!` column are bound to each of its names.
+fn expr_binding(parameter: &Parameter, cell: &Expr) -> proc_macro2::TokenStream {
+    let Parameter {
+        name, ty, elements, ..
+    } = parameter;
+    let ty = ty.as_ref().map(|ty| quote!(: #ty));
+    if elements.is_empty() {
+        quote! { let #name #ty = #cell; }
+    } else {
+        quote! { let (#(#elements),*) #ty = #cell; }
+    }
+}
+
+/// Whether a cell is the path `None`.
+fn is_none(cell: &Expr) -> bool {
+    matches!(cell, Expr::Path(path) if path.qself.is_none() && path.path.is_ident("None"))
+}
+
+/// Copies the body of the test function into a case, for the `inline` option.
+/// The arguments are bound to the function's parameters beforehand.
+fn inline_call(
+    test_fn: &ItemFn,
+    call_args: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let bindings =
+        test_fn
+            .sig
+            .inputs
+            .iter()
+            .zip(call_args)
+            .filter_map(|(input, arg)| match input {
+                // An `impl Trait` type cannot be written in a `let`.
+                FnArg::Typed(typed) if matches!(*typed.ty, Type::ImplTrait(_)) => {
+                    let (cfgs, pat) = (cfg_attrs(input), &typed.pat);
+                    Some(quote! { #(#cfgs)* let #pat = #arg; })
+                }
+                FnArg::Typed(typed) => {
+                    let (cfgs, pat, ty) = (cfg_attrs(input), &typed.pat, &typed.ty);
+                    Some(quote! { #(#cfgs)* let #pat: #ty = #arg; })
+                }
+                FnArg::Receiver(_) => None,
+            });
+    let block = &test_fn.block;
+
+    quote! {
+        {
+            #(#bindings)*
+            #block
+        }
+    }
+}
+
+/// The `#[cfg(...)]` attributes on a parameter of the test function.
+fn cfg_attrs(input: &FnArg) -> Vec<&Attribute> {
+    let attrs = match input {
+        FnArg::Typed(input) => &input.attrs,
+        FnArg::Receiver(receiver) => &receiver.attrs,
+    };
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"))
+        .collect()
+}
+
+/// Prefixes the stem of a case's name with the tags given by the `#[tag(...)]`
+/// attributes on its row, in order.
+fn tagged_stem(tags: &[Attribute], stem: String) -> Result<String> {
+    let mut names = vec![];
+    for tag in tags {
+        let tag = tag.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+        names.extend(tag.iter().map(Ident::to_string));
+    }
+    names.push(stem);
+    Ok(names.join("_"))
+}
+
+/// Whether one of the `#[tag(...)]` attributes on a row gives it the tag.
+fn is_tagged(tags: &[Attribute], name: &str) -> bool {
+    tags.iter().any(|tag| {
+        tag.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)
+            .is_ok_and(|names| names.iter().any(|tag| tag == name))
+    })
+}
+
+/// Whether a test function returns nothing, either by leaving out its return
+/// type or by writing `-> ()`.
+fn returns_unit(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Default => true,
+        ReturnType::Type(_, ty) => matches!(&**ty, Type::Tuple(tuple) if tuple.elems.is_empty()),
+    }
+}
+
+/// Runs the body of a case inside `Bencher::iter`. If the test function returns
+/// a value, such as a `Result`, the first value which is not `Ok` is returned
+/// from the benchmark.
+fn bench_body(
+    bencher: &Ident,
+    output: Option<&ReturnType>,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match output {
+        Some(ReturnType::Type(_, ty)) => quote_spanned! {
+            Span::mixed_site() =>
+            let mut result = Ok(());
+            #bencher.iter(|| {
+                if result.is_ok() {
+                    result = (|| -> #ty { #body })();
+                }
+            });
+            result
+        },
+        _ => quote! {
+            #bencher.iter(|| {
+                #body
+            });
+        },
+    }
+}
+
+/// Declares a constant holding the number of cases generated for the test, for
+/// the `expose_count` option, including those of the attributes stacked above
+/// this one. Rows which are removed by `#[cfg(...)]` or the `if_cfg` option
+/// are not counted.
+fn case_count(
+    options: &Options,
+    test_fn: &ItemFn,
+    test_inputs: &[Row],
+) -> proc_macro2::TokenStream {
+    let name = format_ident!(
+        "{}_CASE_COUNT",
+        test_fn.sig.ident.unraw().to_string().to_uppercase()
+    );
+    let above = options
+        .cases_above
+        .iter()
+        .map(|(cfgs, _)| quote! { #(#cfgs)* () });
+    let feature = options
+        .if_cfg
+        .as_ref()
+        .map(|feature| quote!(#[cfg(feature = #feature)]));
+    let cfgs = test_inputs.iter().map(|row| {
+        let cfgs = row.attrs.iter().filter(|attr| attr.path.is_ident("cfg"));
+        quote! { #feature #(#cfgs)* () }
+    });
+    let cfgs = above.chain(cfgs);
+
+    quote! {
+        #[allow(dead_code)]
+        const #name: usize = <[()]>::len(&[#(#cfgs),*]);
+    }
+}
+
+/// The number of cases above which a table is warned about, unless it is given
+/// the `max_cases` option.
+const MAX_CASES: usize = 1000;
+
+/// Warns that a table generates more cases than its limit, as each one is a
+/// separate function to compile. Procedural macros cannot emit warnings on
+/// stable Rust, so this uses a deprecated item, which is reported at the
+/// header of the table.
+fn case_limit_warning(
+    options: &Options,
+    header: Span,
+    len: usize,
+) -> Option<proc_macro2::TokenStream> {
+    let limit = options.max_cases.unwrap_or(MAX_CASES);
+    if (options.single_test && !options.keep_cases) || len <= limit {
+        return None;
+    }
+
+    let note = format!(
+        "This table generates {} cases, which is more than the limit of {}. Consider using the `single_test` option, or raise the limit with `max_cases`.",
+        len, limit
+    );
+    Some(quote_spanned! {
+        header =>
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct TooManyCases;
+
+            let _ = TooManyCases;
+        };
+    })
+}
+
+/// Checks that the cells of each column have the same type, for the
+/// `homogeneous` option, by putting them in an array which is never evaluated.
+/// The array is given the type of the column's cells, so that cells such as
+/// `None` can be checked, and columns whose type cannot be named there are
+/// left out. Columns whose parameters are generic may have cells of different
+/// types, so they are left out too, as are cells which use the values of
+/// earlier cells and the cells of rows with `#[cfg]` attributes.
+fn homogeneous_columns(
+    options: &Options,
+    test_fn: &ItemFn,
+    column_names: &Punctuated<Column, Token![,]>,
+    test_inputs: &[Row],
+) -> Vec<proc_macro2::TokenStream> {
+    let rows: Vec<_> = test_inputs
+        .iter()
+        .filter(|row| !row.attrs.iter().any(|attr| attr.path.is_ident("cfg")))
+        .collect();
+    let mut inputs = test_fn.sig.inputs.iter().skip(options.implicit_count());
+    let mut checks = vec![];
+    for (idx, column) in column_names.iter().enumerate() {
+        let ty = match column {
+            Column::Parameter(parameter) => {
+                let inputs: Vec<_> = inputs.by_ref().take(parameter.width()).collect();
+                let generic = inputs.iter().any(
+                    |input| matches!(input, FnArg::Typed(input) if is_generic(test_fn, &input.ty)),
+                );
+                if generic || parameter.cells == Cells::Strategies {
+                    continue;
+                }
+                // The type of a transformed column is the type of the cells
+                // after the transform, so the cells as they are written may
+                // have any type. Without a type in the header, the cells have
+                // the type of their parameter, if it can be named here.
+                let param_ty = match (parameter.cells, &inputs[..]) {
+                    (Cells::Values | Cells::Platforms | Cells::Tuples, [FnArg::Typed(input)])
+                        if is_nameable(test_fn, &input.ty) =>
+                    {
+                        Some(&input.ty)
+                    }
+                    _ => None,
+                };
+                match parameter.ty.as_ref().or(param_ty) {
+                    Some(ty) if parameter.transform.is_none() => ty,
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        let earlier: Vec<_> = column_names
+            .iter()
+            .take(idx)
+            .filter_map(|column| match column {
+                Column::Parameter(Parameter { name, .. }) => Some(name),
+                _ => None,
+            })
+            .collect();
+        let cells: Vec<_> = rows
+            .iter()
+            .map(|row| &row.args[idx])
+            .filter(|cell| earlier.iter().all(|name| mention(cell, name).is_none()))
+            .collect();
+        let len = cells.len();
+        if len < 2 {
+            continue;
+        }
+        checks.push(quote! {
+            const _: fn() = || {
+                let _: [#ty; #len] = [#(#cells),*];
+            };
+        });
+    }
+    checks
+}
+
+/// The name of the lock held by each case with the `serial` option.
+fn serial_lock_name(test_fn: &ItemFn) -> Ident {
+    format_ident!(
+        "{}_SERIAL_LOCK",
+        test_fn.sig.ident.unraw().to_string().to_uppercase()
+    )
+}
+
+/// Declares the lock held by each case with the `serial` option. It is shared
+/// by the cases of every attribute stacked on the test function.
+fn serial_lock(test_fn: &ItemFn) -> proc_macro2::TokenStream {
+    let name = serial_lock_name(test_fn);
+    quote! {
+        #[allow(dead_code)]
+        static #name: ::std::sync::Mutex<()> = ::std::sync::Mutex::new(());
+    }
+}
+
+/// Takes the lock of the `serial` option at the start of a case. A case which
+/// panicked while holding the lock does not stop the others from taking it.
+fn serial_guard(options: &Options, test_fn: &ItemFn) -> Option<proc_macro2::TokenStream> {
+    let name = serial_lock_name(test_fn);
+    options.serial.then(|| {
+        quote_spanned! {
+            Span::mixed_site() =>
+            let _serial = #name.lock().unwrap_or_else(|error| error.into_inner());
+        }
+    })
+}
+
+/// Compares the debug representation of a case's value with the line recorded
+/// for the case in the file of the `golden` option. Setting the `UPDATE_GOLDEN`
+/// environment variable records the value instead. The file is locked while it
+/// is rewritten, as the cases run in parallel, and its lines are kept sorted so
+/// that it does not change when the cases finish in a different order.
+fn golden_check(
+    path: &LitStr,
+    key: &str,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote_spanned! {
+        Span::mixed_site() =>
+        let actual = ::std::format!("{:?}", #value);
+        let path = ::std::path::Path::new(::std::env!("CARGO_MANIFEST_DIR")).join(#path);
+        let prefix = ::std::format!("{}: ", #key);
+        if ::std::env::var_os("UPDATE_GOLDEN").is_some() {
+            if let ::std::option::Option::Some(dir) = path.parent() {
+                let _ = ::std::fs::create_dir_all(dir);
+            }
+            // A lock left behind by a process which was killed is reported,
+            // rather than waited on forever.
+            let lock = ::std::format!("{}.lock", path.display());
+            let start = ::std::time::Instant::now();
+            while let ::std::result::Result::Err(error) = ::std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock)
+            {
+                if error.kind() != ::std::io::ErrorKind::AlreadyExists {
+                    panic!("Could not lock the golden file `{}`: {}", path.display(), error);
+                }
+                if start.elapsed() > ::std::time::Duration::from_secs(10) {
+                    panic!(
+                        "The golden file `{}` is still locked after 10 seconds. If no tests are running, delete `{}`.",
+                        path.display(),
+                        lock
+                    );
+                }
+                ::std::thread::sleep(::std::time::Duration::from_millis(1));
+            }
+            let contents = ::std::fs::read_to_string(&path).unwrap_or_default();
+            let mut lines: ::std::vec::Vec<&str> = contents
+                .lines()
+                .filter(|line| !line.starts_with(&prefix))
+                .collect();
+            let line = ::std::format!("{}{}", prefix, actual);
+            lines.push(&line);
+            lines.sort_unstable();
+            // The file is replaced in one step, so that it is never read while
+            // it is only partly written.
+            let temporary = ::std::format!("{}.{}.tmp", path.display(), ::std::process::id());
+            let written = ::std::fs::write(&temporary, lines.join("\n") + "\n")
+                .and_then(|_| ::std::fs::rename(&temporary, &path));
+            let _ = ::std::fs::remove_file(&lock);
+            if let ::std::result::Result::Err(error) = written {
+                panic!("Could not write the golden file `{}`: {}", path.display(), error);
+            }
+        } else {
+            let contents = ::std::fs::read_to_string(&path).unwrap_or_else(|error| {
+                panic!("Could not read the golden file `{}`: {}", path.display(), error)
+            });
+            let expected = contents
+                .lines()
+                .find_map(|line| line.strip_prefix(&prefix))
+                .unwrap_or_else(|| {
+                    panic!(
+                        "The golden file `{}` has no value for `{}`. Run the tests with `UPDATE_GOLDEN=1` to record it.",
+                        path.display(),
+                        #key
+                    )
+                });
+            assert_eq!(
+                actual, expected,
+                "The value of `{}` does not match the golden file `{}`. Run the tests with `UPDATE_GOLDEN=1` to update it.",
+                #key,
+                path.display()
+            );
+        }
+    }
+}
+
+/// Reads the text of a row's `#[note = "..."]` attribute.
+fn note_text(note: &Attribute) -> Result<String> {
+    match note.parse_meta()? {
+        Meta::NameValue(MetaNameValue {
+            lit: Lit::Str(text),
+            ..
+        }) => Ok(text.value()),
+        _ => Err(syn::Error::new_spanned(
+            note,
+            "A note must be written as `#[note = \"...\"]`.",
+        )),
+    }
+}
+
+/// Wraps the body of a case with a `#[timeout(ms)]` attribute so that it runs on
+/// another thread, failing the case if it does not finish in time.
+fn with_timeout(
+    timeout: &Attribute,
+    fn_name: &Ident,
+    body: proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream> {
+    let millis: u64 = timeout.parse_args::<LitInt>()?.base10_parse()?;
+    let message = format!("Case `{}` did not finish within {}ms.", fn_name, millis);
+
+    Ok(quote_spanned! {
+        Span::mixed_site() =>
+        let (sender, receiver) = ::std::sync::mpsc::channel();
+        let case = ::std::thread::spawn(move || {
+            let value = { #body };
+            let _ = sender.send(value);
+        });
+        match receiver.recv_timeout(::std::time::Duration::from_millis(#millis)) {
+            Ok(value) => value,
+            Err(::std::sync::mpsc::RecvTimeoutError::Timeout) => panic!("{}", #message),
+            Err(::std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                ::std::panic::resume_unwind(case.join().unwrap_err())
+            }
+        }
+    })
+}
+
+/// Catches a panic in the body of a case, for the `show_cells` option, and
+/// panics again with the cells of its row added to the message.
+fn with_cells(
+    column_names: &Punctuated<Column, Token![,]>,
+    args: &Punctuated<Expr, Token![,]>,
+    output: Option<&ReturnType>,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let cells = column_names
+        .iter()
+        .zip(args.iter())
+        .filter(|(column, _)| !matches!(column, Column::CaseName(_)))
+        .map(|(column, arg)| format!("{}={}", column.label(), quote!(#arg)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let output = output.filter(|output| matches!(output, ReturnType::Type(..)));
+
+    quote_spanned! {
+        Span::mixed_site() =>
+        let case = ::std::panic::AssertUnwindSafe(|| #output { #body });
+        match ::std::panic::catch_unwind(case) {
+            Ok(value) => value,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("The case panicked.");
+                panic!("{}\n{}", message, #cells)
+            }
+        }
+    }
+}
+
+/// Generates a single test which runs every case in a loop, for the
+/// `single_test` option.
+fn single_test(
+    options: &Options,
+    test_fn: &ItemFn,
+    test_attr: &TestAttr,
+    case_attrs: &[Attribute],
+    column_names: &Punctuated<Column, Token![,]>,
+    test_inputs: &[Row],
+    stems: &[String],
+) -> Result<proc_macro2::TokenStream> {
+    for column in column_names.iter() {
+        if let Column::TypeArgument(_)
+        | Column::ConstArgument(_)
+        | Column::Pattern(_)
+        | Column::Expected(_)
+        | Column::ErrorMessage(_)
+        | Column::PanicFlag(_)
+        | Column::Check(_)
+        | Column::Parameter(Parameter {
+            cells: Cells::Spreads,
+            ..
+        }) = column
+        {
+            return Err(syn::Error::new_spanned(
+                column,
+                "This column cannot be used with the `single_test` option.",
+            ));
+        }
+    }
+    if let Some(attr) = test_inputs.iter().flat_map(|row| row.attrs.iter()).next() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "Rows cannot have attributes when using the `single_test` option.",
+        ));
+    }
+    if let Some(attr) = test_fn.sig.inputs.iter().flat_map(cfg_attrs).next() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "Parameters cannot have `#[cfg]` attributes when using the `single_test` option.",
+        ));
+    }
+
+    let parameters: Vec<_> = column_names
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, column)| match column {
+            Column::Parameter(parameter) => Some((idx, parameter)),
+            _ => None,
+        })
+        .collect();
+    let names: Vec<_> = parameters
+        .iter()
+        .map(|(_, parameter)| &parameter.name)
+        .collect();
+    let types = parameters.iter().map(|(_, parameter)| match &parameter.ty {
+        Some(ty) if !matches!(**ty, Type::ImplTrait(_)) => quote!(#ty),
+        _ => quote!(_),
+    });
+    // The details of each case are passed to the test function as extra
+    // elements of its row.
+    let rows = test_inputs.iter().zip(stems).map(|(row, stem)| {
+        let details = case_details(options, test_fn, row, stem);
+        let cells = parameters
+            .iter()
+            .map(|(idx, _)| column_names[*idx].transformed(row.args[*idx].clone()));
+        quote! { (#(#details,)* #(#cells,)*) }
+    });
+    let (details, detail_types): (Vec<_>, Vec<_>) = case_detail_types(options).into_iter().unzip();
+    let len = test_inputs.len();
+
+    let fn_name = combined_name(options, test_fn);
+    let call = options.call_target(test_fn);
+    let turbofish = options.turbofish();
+    let asyncness = &test_fn.sig.asyncness;
+    let awaited = asyncness.map(|_| quote!(.await));
+    let (fixture, setup_binding) = setup_binding(options, test_fn).unzip();
+    let teardown = teardown_guard(options, test_fn);
+    let awaited_teardown = awaited_teardown(options, test_fn);
+    let fixture = fixture.map(|fixture| quote!(#fixture,));
+    let (fixtures, fixture_bindings): (Vec<_>, Vec<_>) =
+        fixture_bindings(options).into_iter().unzip();
+    let call = quote! {
+        #call #turbofish(#(#details,)* #fixture #(#fixtures,)* #(#names),*)#awaited
+    };
+
+    // The variables of the loop are hygienic, so that they cannot clash with
+    // the names of the columns.
+    let result = Ident::new("result", Span::mixed_site());
+    let label = Ident::new("label", Span::mixed_site());
+    let case_guard = Ident::new("guard", Span::mixed_site());
+    let cases = Ident::new("cases", Span::mixed_site());
+    let labels = Ident::new("labels", Span::mixed_site());
+    let (output, body, tail) = match &test_fn.sig.output {
+        ReturnType::Default => (None, quote! { #call; #awaited_teardown }, None),
+        output if options.no_std => (
+            Some(output),
+            quote! {
+                let #result = #call;
+                #awaited_teardown
+                #result?;
+            },
+            Some(quote!(Ok(()))),
+        ),
+        output => (
+            Some(output),
+            quote! {
+                let #result = #call;
+                #awaited_teardown
+                if #result.is_err() {
+                    ::std::eprintln!("Case `{}` failed.", #label);
+                    return #result;
+                }
+            },
+            Some(quote!(Ok(()))),
+        ),
+    };
+
+    // Without `std`, there is no way to report which case failed.
+    let (label_pattern, guard, guard_type) = if options.no_std {
+        (quote!(_), None, None)
+    } else {
+        (
+            quote!(#label),
+            Some(quote! { let #case_guard = CaseGuard(#label); }),
+            Some(quote! {
+                struct CaseGuard(&'static str);
+
+                impl Drop for CaseGuard {
+                    fn drop(&mut self) {
+                        if ::std::thread::panicking() {
+                            ::std::eprintln!("Case `{}` failed.", self.0);
+                        }
+                    }
+                }
+            }),
+        )
+    };
+    let cases = quote! {
+        let #cases: [(#(#detail_types,)* #(#types,)*); #len] = [#(#rows),*];
+        let #labels: [&'static str; #len] = [#(#stems),*];
+        for (#label_pattern, (#(#details,)* #(#names,)*)) in #labels.into_iter().zip(#cases) {
+            #guard
+            #setup_binding
+            #(#fixture_bindings)*
+            #teardown
+            #body
+        }
+    };
+    let cases = match options.repeat {
+        Some(repeat) => quote! {
+            for _ in 0..#repeat {
+                #cases
+            }
+        },
+        None => cases,
+    };
+
+    Ok(quote! {
+        #[#test_attr]
+        #(#case_attrs)*
+        #asyncness fn #fn_name() #output {
+            #guard_type
+            #cases
+            #tail
+        }
+    })
+}
+
+/// The name of the test generated by the `single_test` option.
+fn combined_name(options: &Options, test_fn: &ItemFn) -> Ident {
+    if options.module {
+        format_ident!("all")
+    } else {
+        format_ident!("{}_all", test_fn.sig.ident)
+    }
+}
+
+/// The details of a case which are passed to the test function before its
+/// cells, for the `case_name` and `expose_index` options.
+fn case_details(
+    options: &Options,
+    test_fn: &ItemFn,
+    row: &Row,
+    stem: &str,
+) -> Vec<proc_macro2::TokenStream> {
+    let mut details = vec![];
+    if options.case_name {
+        let case_name = options.case_fn_name(test_fn, stem);
+        details.push(quote!(#case_name));
+    }
+    if options.expose_index {
+        let index = row.index[0] + options.first_index();
+        details.push(quote!(#index));
+    }
+    details
+}
+
+/// The variables and types of the details returned by `case_details`, for the
+/// `single_test` option.
+fn case_detail_types(options: &Options) -> Vec<(Ident, proc_macro2::TokenStream)> {
+    let mut types = vec![];
+    if options.case_name {
+        let case_name = Ident::new("case_name", Span::mixed_site());
+        types.push((case_name, quote!(&'static str)));
+    }
+    if options.expose_index {
+        let index = Ident::new("case_index", Span::mixed_site());
+        types.push((index, quote!(usize)));
+    }
+    types
+}
+
+/// The argument which passes the value of the `setup` option to the test
+/// function, along with the statement which binds it, if the option was given.
+/// The value is bound before the call so that a block is not passed as an
+/// argument. If the test function takes a reference to the fixture, it is
+/// lent rather than moved, and so is dropped at the end of the case. A
+/// `'static` reference cannot be lent from a fixture which only lives for the
+/// case, so the setup is taken to give the reference itself.
+fn setup_binding(
+    options: &Options,
+    test_fn: &ItemFn,
+) -> Option<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let fixture = Ident::new("fixture", Span::mixed_site());
+    if options.tempdir {
+        return Some((
+            quote!(#fixture.path()),
+            quote! {
+                let #fixture = ::tempfile::TempDir::new()
+                    .expect("The temporary directory for this case could not be created.");
+            },
+        ));
+    }
+    let setup = options.setup.as_ref()?;
+
+    let position = usize::from(options.case_name) + usize::from(options.expose_index);
+    let reference = match test_fn.sig.inputs.iter().nth(position) {
+        Some(FnArg::Typed(input)) => match &*input.ty {
+            Type::Reference(reference) if !matches!(&reference.lifetime, Some(lifetime) if lifetime.ident == "static") => {
+                Some(reference)
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    Some(match reference {
+        Some(reference) if reference.mutability.is_some() => {
+            (quote!(&mut #fixture), quote! { let mut #fixture = #setup; })
+        }
+        Some(_) => (quote!(&#fixture), quote! { let #fixture = #setup; }),
+        None => (quote!(#fixture), quote! { let #fixture = #setup; }),
+    })
+}
+
+/// The arguments which pass the results of the `fixtures` option to the test
+/// function, along with the statements which bind them. Each fixture is
+/// called afresh for each case, in the order that they were given.
+fn fixture_bindings(
+    options: &Options,
+) -> Vec<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    options
+        .fixtures
+        .iter()
+        .flatten()
+        .map(|fixture| {
+            let binding = format_ident!("fixture_{}", fixture, span = Span::mixed_site());
+            (quote!(#binding), quote! { let #binding = #fixture(); })
+        })
+        .collect()
+}
+
+/// Declares a guard which evaluates the `teardown` expression when it is
+/// dropped, so that it runs at the end of a case even if the case panics.
+fn teardown_guard(options: &Options, test_fn: &ItemFn) -> Option<proc_macro2::TokenStream> {
+    let teardown = options.teardown.as_ref()?;
+    if awaits_teardown(options, test_fn) {
+        return None;
+    }
+
+    Some(quote_spanned! {
+        Span::mixed_site() =>
+        struct Teardown;
+
+        impl Drop for Teardown {
+            fn drop(&mut self) {
+                let _ = #teardown;
+            }
+        }
+
+        let _teardown = Teardown;
+    })
+}
+
+/// Evaluates the `teardown` expression of an async case once the test function
+/// has returned, if it awaits a future, as it cannot be awaited in a guard.
+fn awaited_teardown(options: &Options, test_fn: &ItemFn) -> Option<proc_macro2::TokenStream> {
+    let teardown = options.teardown.as_ref()?;
+    if !awaits_teardown(options, test_fn) {
+        return None;
+    }
+
+    Some(quote! { let _ = #teardown; })
+}
+
+/// Whether the test function is `async` and its `teardown` expression uses
+/// `.await`.
+fn awaits_teardown(options: &Options, test_fn: &ItemFn) -> bool {
+    test_fn.sig.asyncness.is_some()
+        && options
+            .teardown
+            .as_ref()
+            .is_some_and(|teardown| contains_await(quote!(#teardown)))
+}
+
+/// Whether the tokens contain `.await`, including inside any delimited group.
+fn contains_await(tokens: proc_macro2::TokenStream) -> bool {
+    let mut after_dot = false;
+    for token in tokens {
+        match token {
+            proc_macro2::TokenTree::Ident(ident) if after_dot && ident == "await" => return true,
+            proc_macro2::TokenTree::Group(group) if contains_await(group.stream()) => return true,
+            proc_macro2::TokenTree::Punct(punct) => {
+                after_dot = punct.as_char() == '.';
+                continue;
+            }
+            _ => {}
+        }
+        after_dot = false;
+    }
+    false
+}
+
+/// Declares a parameter table which can be shared by several tests.
+///
+/// The table is written in the same way as for `test_with_parameters`, after
+/// a name and an `=`. Tests can then use the table with
+/// `#[test_with_parameters(use name)]`, optionally preceded by any options for
+/// that test. Each test generates the same cases from the table.
+///
+/// The table is declared as a `macro_rules!` macro, so it follows the same
+/// scoping rules, and must be declared before the tests which use it.
+///
+/// <br>
+///
+/// # Example
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///     parameter_table! {
+///         additions =
+///         [ input  , expected ]
+///         [ (1, 1) , 2        ]
+///         [ (2, 2) , 4        ]
+///     }
+///
+///     #[test_with_parameters(use additions)]
+///     fn add_works(input: (usize, usize), expected: usize) {
+///         let (left, right) = input;
+///         assert_eq!(left + right, expected)
+///     }
+///
+///     #[test_with_parameters(module, use additions)]
+///     fn add_is_commutative(input: (usize, usize), expected: usize) {
+///         let (left, right) = input;
+///         assert_eq!(right + left, expected)
+///     }
+/// }
+/// ```
+#[proc_macro]
+pub fn parameter_table(input: TokenStream) -> TokenStream {
+    let ParameterTableSyntax { name, table } =
+        syn::parse_macro_input!(input as ParameterTableSyntax);
+
+    (quote! {
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            (($($options:tt)*) $($item:tt)*) => {
+                #[::test_with_parameters::test_with_parameters($($options)* #table)]
+                $($item)*
+            };
+        }
+    })
+    .into()
+}
+
+/// Expands a parameter table into an array of tuples, for use outside of
+/// tests.
+///
+/// The table is written in the same way as for `test_with_parameters`, and each
+/// row becomes a tuple of its cells. As with any array, the cells in each
+/// column must have the same type, which is inferred from the cells unless the
+/// column is given a type. Only the columns of parameters can be used, and
+/// rows may only have `#[cfg(...)]` attributes.
+///
+/// <br>
+///
+/// # Example
+///
+/// ```
+/// # use test_with_parameters::parameters;
+/// const CASES: [(i32, i32); 2] = parameters!(
+///     [ input , expected ]
+///     [ 1     , 2        ]
+///     [ -1    , 0        ]
+/// );
+///
+/// for (input, expected) in CASES {
+///     assert_eq!(input + 1, expected);
+/// }
+/// ```
+#[proc_macro]
+pub fn parameters(input: TokenStream) -> TokenStream {
+    let TableSyntax {
+        column_names,
+        test_inputs,
+        ..
+    } = syn::parse_macro_input!(input as TableSyntax);
+
+    match parameter_array(&column_names, &test_inputs) {
+        Ok(array) => array.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Generates the array of tuples for `parameters!`.
+fn parameter_array(
+    column_names: &Punctuated<Column, Token![,]>,
+    test_inputs: &[Row],
+) -> Result<proc_macro2::TokenStream> {
+    check_row_lengths(column_names, test_inputs)?;
+
+    let mut parameters = Vec::with_capacity(column_names.len());
+    for column in column_names.iter() {
+        match column {
+            Column::Parameter(parameter)
+                if !matches!(parameter.cells, Cells::Strategies | Cells::Statics) =>
+            {
+                parameters.push(parameter)
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    column,
+                    "This column cannot be used with `parameters!`.",
+                ))
+            }
+        }
+    }
+
+    let mut rows = Vec::with_capacity(test_inputs.len());
+    for row in test_inputs {
+        if let Some(attr) = row.attrs.iter().find(|attr| !attr.path.is_ident("cfg")) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "Rows can only have `#[cfg]` attributes in `parameters!`.",
+            ));
+        }
+        // Cells in typed columns are bound to a variable of that type, so that
+        // the type of the array can be inferred.
+        let cells =
+            parameters
+                .iter()
+                .zip(row.args.iter())
+                .map(|(parameter, cell)| match &parameter.ty {
+                    Some(ty) => {
+                        let name = &parameter.name;
+                        quote!({ let #name: #ty = #cell; #name })
+                    }
+                    None => quote!(#cell),
+                });
+        let attrs = &row.attrs;
+        rows.push(quote! { #(#attrs)* (#(#cells,)*) });
+    }
+
+    Ok(quote! {
+        [#(#rows),*]
+    })
+}
+
+/// Whether the attribute refers to a table declared with `parameter_table!`.
+fn uses_shared_table(attr: &proc_macro2::TokenStream) -> bool {
+    attr.clone().into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == "use",
+        _ => false,
+    })
+}
+
+/// The next `#[test_with_parameters]` attribute on the test function, if there
+/// is one.
+fn stacked_attr(test_fn: &mut ItemFn) -> Option<&mut Attribute> {
+    test_fn.attrs.iter_mut().find(|attr| {
+        attr.path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "test_with_parameters")
+    })
+}
+
+/// Passes the number of rows in this attribute's table on to the next
+/// `#[test_with_parameters]` attribute on the test function, if there is one,
+/// so that the cases of stacked attributes are numbered one after another.
+fn number_stacked_table(
+    options: &Options,
+    test_fn: &mut ItemFn,
+    test_inputs: &[Row],
+) -> Result<bool> {
+    let stacked = match stacked_attr(test_fn) {
+        Some(stacked) => stacked,
+        None => return Ok(false),
+    };
+
+    let key_span = |key| options.key(key).map_or_else(Span::call_site, Ident::span);
+    let conflict = first_conflict(&[(options.inline, "inline"), (options.no_helper, "no_helper")]);
+    if let Some(conflict) = conflict {
+        return Err(syn::Error::new(
+            key_span(conflict),
+            format!(
+                "The `{}` option cannot be used when another `#[test_with_parameters]` attribute is stacked below this one, as that attribute would be removed along with the test function.",
+                conflict
+            ),
+        ));
+    }
+    let declared = first_conflict(&[
+        (options.expose_names, "expose_names"),
+        (options.expose_count, "expose_count"),
+    ]);
+    if let Some(declared) = declared {
+        return Err(syn::Error::new(
+            key_span(declared),
+            format!(
+                "The `{}` option must be given to the last `#[test_with_parameters]` attribute on the test function, which declares it for the cases of every attribute.",
+                declared
+            ),
+        ));
+    }
+
+    let rows = test_inputs
+        .iter()
+        .map(|row| row.index[0] + 1)
+        .max()
+        .unwrap_or(0);
+    let offset = options.first_index() + rows;
+    let table = syn::parse2::<proc_macro2::Group>(stacked.tokens.clone())
+        .map(|group| group.stream())
+        .unwrap_or_default();
+    let serial = (options.serial || options.serial_above).then(|| quote!(, serial));
+    let module = (options.module || options.module_above).then(|| quote!(, module));
+    let single_test = (options.single_test && !options.module || options.single_test_above)
+        .then(|| quote!(, single_test));
+    let passed_on = passed_on(quote!(offset = #offset #serial #module #single_test));
+    stacked.tokens = quote!((#passed_on, #table));
+    Ok(true)
+}
+
+/// Finds where a cell refers to the name of a column. Names which are part of
+/// a longer path, fields, macros and closure parameters are not references.
+fn mention(cell: &Expr, name: &Ident) -> Option<Span> {
+    fn find(tokens: proc_macro2::TokenStream, name: &Ident) -> Option<Span> {
+        let tokens: Vec<_> = tokens.into_iter().collect();
+        let is_punct = |idx: Option<usize>, chars: &[char]| {
+            matches!(
+                idx.and_then(|idx| tokens.get(idx)),
+                Some(proc_macro2::TokenTree::Punct(punct)) if chars.contains(&punct.as_char())
+            )
+        };
+        tokens
+            .iter()
+            .enumerate()
+            .find_map(|(idx, token)| match token {
+                proc_macro2::TokenTree::Ident(ident)
+                    if ident == name
+                        && !is_punct(idx.checked_sub(1), &['.', ':', '|'])
+                        && !is_punct(Some(idx + 1), &[':', '!']) =>
+                {
+                    Some(ident.span())
+                }
+                proc_macro2::TokenTree::Group(group) => find(group.stream(), name),
+                _ => None,
+            })
+    }
+    find(cell.to_token_stream(), name)
+}
+
+/// Fails if a cell of a `spread!` column is a tuple with a different number
+/// of elements to the parameters which the column covers.
+fn spread_error(column_names: &Punctuated<Column, Token![,]>, row: &Row) -> Option<syn::Error> {
+    let mut cells = column_names.iter().zip(row.args.iter());
+    cells.find_map(|(column, cell)| match (column, cell) {
+        (
+            Column::Parameter(
+                parameter @ Parameter {
+                    cells: Cells::Spreads,
+                    ..
+                },
+            ),
+            Expr::Tuple(tuple),
+        ) if tuple.elems.len() != parameter.width() => Some(syn::Error::new_spanned(
+            cell,
+            format!(
+                "This tuple has {} elements, but the `{}` column spreads it across {} parameters.",
+                tuple.elems.len(),
+                column.label(),
+                parameter.width()
+            ),
+        )),
+        _ => None,
+    })
+}
+
+/// Fails if a cell refers to a later column of its row, whose value is not
+/// yet known when the cell is evaluated. Items in scope are not known to the
+/// macro, so one which shares a name with a later column is also reported.
+fn forward_reference_error(
+    column_names: &Punctuated<Column, Token![,]>,
+    row: &Row,
+) -> Option<syn::Error> {
+    let cells = column_names.iter().zip(row.args.iter()).enumerate();
+    for (idx, (column, cell)) in cells {
+        if matches!(
+            column,
+            Column::CaseName(_) | Column::TypeArgument(_) | Column::Pattern(_)
+        ) {
+            continue;
+        }
+        for later in column_names.iter().skip(idx + 1) {
+            let span = match later {
+                Column::Parameter(Parameter { name, .. }) => mention(cell, name),
+                _ => None,
+            };
+            if let Some(span) = span {
+                return Some(syn::Error::new(
+                    span,
+                    format!(
+                        "The `{}` column comes after this cell, so its value cannot be used here.",
+                        later.label()
+                    ),
+                ));
+            }
+        }
+    }
+    None
+}
+
+/// Whether an attribute on the test function should be applied to each of the
+/// generated cases.
+fn is_case_attr(attr: &Attribute) -> bool {
+    is_test_only_attr(attr) || attr.path.is_ident("cfg")
+}
+
+/// Whether an attribute only makes sense on a `#[test]` function, and so must
+/// be removed from the helper function.
+fn is_test_only_attr(attr: &Attribute) -> bool {
+    applies_attr(attr, "ignore") || applies_attr(attr, "should_panic")
+}
+
+/// Whether an attribute is the attribute with the given name, or a
+/// `#[cfg_attr(...)]` which may apply it. The condition of a `cfg_attr` cannot
+/// be evaluated by the macro, so it is assumed to hold.
+fn applies_attr(attr: &Attribute, name: &str) -> bool {
+    fn applies(meta: &Meta, name: &str) -> bool {
+        match meta {
+            Meta::List(list) if list.path.is_ident("cfg_attr") => {
+                list.nested.iter().skip(1).any(|nested| match nested {
+                    syn::NestedMeta::Meta(meta) => applies(meta, name),
+                    syn::NestedMeta::Lit(_) => false,
+                })
+            }
+            _ => meta.path().is_ident(name),
+        }
+    }
+
+    if attr.path.is_ident(name) {
+        return true;
+    }
+    attr.path.is_ident("cfg_attr") && attr.parse_meta().is_ok_and(|meta| applies(&meta, name))
+}
+
+/// Checks that a row has one cell for each column of the table.
+fn row_length_error(column_names: &Punctuated<Column, Token![,]>, row: &Row) -> Option<syn::Error> {
+    let expected = column_names.len();
+    if row.args.len() == expected {
+        return None;
+    }
+
+    // Rows may leave out the trailing columns which have defaults, though they
+    // have already been filled in for rows which do.
+    let optional = column_names
+        .iter()
+        .rev()
+        .take_while(|column| column.default().is_some())
+        .count();
+    let count = match optional {
+        0 => expected.to_string(),
+        optional => format!("between {} and {}", expected - optional, expected),
+    };
+    let message = format!(
+        "Expected {} argument{} ({}) but found {}.",
+        count,
+        if expected == 1 { "" } else { "s" },
+        column_names
+            .iter()
+            .map(Column::label)
+            .collect::<Vec<_>>()
+            .join(", "),
+        row.args.len(),
+    );
+    let span = match row.args.iter().nth(expected) {
+        Some(extra) => extra.span(),
+        None => row.bracket.span,
+    };
+    Some(syn::Error::new(span, message))
+}
+
+/// Hashes a row's tokens with 32-bit FNV-1a, which is used rather than the
+/// standard library's hasher as its output must not change between releases.
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Makes the table for the `from` option, which has a column for each of the
+/// test function's parameters and a row for each element of the array. Each
+/// row passes the fields of its element to the test function, or the element
+/// itself if the function has one parameter.
+fn from_table(
+    options: &Options,
+    test_fn: &ItemFn,
+    from: &Expr,
+    count: usize,
+) -> (Punctuated<Column, Token![,]>, Vec<Row>) {
+    let implicit_count = options.implicit_count();
+    let inputs: Vec<_> = test_fn.sig.inputs.iter().skip(implicit_count).collect();
+
+    let column_names = inputs
+        .iter()
+        .enumerate()
+        .map(|(idx, input)| {
+            let name = match input {
+                FnArg::Typed(input) => match &*input.pat {
+                    Pat::Ident(pat) => pat.ident.clone(),
+                    pat => format_ident!("field{}", idx, span = pat.span()),
+                },
+                FnArg::Receiver(receiver) => format_ident!("field{}", idx, span = receiver.span()),
+            };
+            Column::Parameter(Parameter {
+                name,
+                ty: None,
+                default: None,
+                transform: None,
+                cells: Cells::Values,
+                elements: vec![],
+            })
+        })
+        .collect();
+
+    let test_inputs = (0..count)
+        .map(|position| {
+            let index = syn::Index::from(position);
+            let mut args = Punctuated::new();
+            if inputs.len() == 1 {
+                args.push(syn::parse_quote!(#from[#index]));
+            } else {
+                for field in (0..inputs.len()).map(syn::Index::from) {
+                    args.push(syn::parse_quote!(#from[#index].#field));
+                }
+            }
+            Row {
+                attrs: vec![],
+                index: vec![position],
+                bracket: token::Bracket(from.span()),
+                args,
+                source: Some(format!("index {} of `{}`", position, quote!(#from))),
+            }
+        })
+        .collect();
+
+    (column_names, test_inputs)
+}
+
+/// Checks that the array given by the `from` option has as many elements as the
+/// `count` option says, when the crate is compiled, as the macro cannot see its
+/// length.
+fn from_length_check(options: &Options) -> Option<proc_macro2::TokenStream> {
+    let from = options.from.as_ref()?;
+    let count = options.count?;
+    let message = format!(
+        "`{}` must have {} elements, as given by the `count` option.",
+        quote!(#from),
+        count
+    )
+    .replace('{', "{{")
+    .replace('}', "}}");
+    Some(quote_spanned! {
+        from.span() =>
+        const _: () = ::core::assert!(#from.len() == #count, #message);
+    })
+}
+
+/// The name of a case inside the module generated by the `module` option,
+/// which is its stem on its own. Keywords are made into raw identifiers, and
+/// names which cannot be identifiers at all are prefixed with `case_`.
+fn module_case_ident(stem: &str) -> Ident {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+        "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+        "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+    // These are keywords which cannot be raw identifiers.
+    const RESERVED: &[&str] = &["crate", "self", "super", "_"];
+
+    if stem.starts_with(|c: char| c.is_ascii_digit()) || RESERVED.contains(&stem) {
+        format_ident!("case_{}", stem)
+    } else if KEYWORDS.contains(&stem) {
+        Ident::new_raw(stem, Span::call_site())
+    } else {
+        format_ident!("{}", stem)
+    }
+}
+
+/// Joins the names given to a row by its `name!` columns, failing if any of
+/// its cells cannot be used as a name.
+fn named(name_columns: &[usize], args: &Punctuated<Expr, Token![,]>) -> Result<String> {
+    let mut errors = Errors::default();
+    let mut names = Vec::with_capacity(name_columns.len());
+    for &column in name_columns.iter() {
+        match case_name(&args[column]) {
+            Ok(name) => names.push(name),
+            Err(error) => errors.push(error),
+        }
+    }
+    errors.finish()?;
+    Ok(names.join("_"))
+}
+
+/// Converts a cell from the `name!` column into a fragment which can be used
+/// in an identifier, failing if the cell cannot be used as a name.
+fn case_name(cell: &Expr) -> Result<String> {
+    let name = match cell {
+        Expr::Path(path) if path.qself.is_none() && path.path.get_ident().is_some() => {
+            path.path.segments[0].ident.unraw().to_string()
+        }
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(s), ..
+        }) => s.value().to_lowercase(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                cell,
+                "Case names must be identifiers or string literals.",
+            ))
+        }
+    };
+
+    let sanitized = name
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    if sanitized.is_empty() {
+        Err(syn::Error::new_spanned(
+            cell,
+            "This case name is empty. It must contain at least one letter or digit.",
+        ))
+    } else if syn::parse_str::<Ident>(&format!("_{}", sanitized)).is_err() {
+        // Some alphanumeric characters, such as `²`, cannot be used in
+        // identifiers.
+        Err(syn::Error::new_spanned(
+            cell,
+            "This case name contains a character which cannot be used in an identifier.",
+        ))
+    } else {
+        Ok(sanitized)
+    }
+}
+
+mod kw {
+    syn::custom_keyword!(cross);
+    syn::custom_keyword!(matrix);
+    syn::custom_keyword!(rows);
+    syn::custom_keyword!(skip);
+    syn::custom_keyword!(with);
+}
+
+struct TableSyntax {
+    options: Options,
+    header: token::Bracket,
+    column_names: Punctuated<Column, Token![,]>,
+    test_inputs: Vec<Row>,
+}
+
+impl Parse for TableSyntax {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let options: Options = input.parse()?;
+
+        if let (Some(_), Some(from)) = (&options.csv, &options.from) {
+            return Err(syn::Error::new_spanned(
+                from,
+                "The `from` option cannot be used with the `csv` option.",
+            ));
+        }
+
+        if let Some(path) = &options.csv {
+            if !input.is_empty() {
+                return Err(input.error("A table cannot be given as well as a `csv` file."));
+            }
+
+            let (header, column_names, test_inputs) = parse_csv(path, &options)?;
+            return Ok(TableSyntax {
+                options,
+                header,
+                column_names,
+                test_inputs,
+            });
+        }
+
+        // The table of a `from` option is made once the test function's
+        // parameters are known.
+        match (&options.from, options.count) {
+            (Some(from), Some(_)) => {
+                if !input.is_empty() {
+                    return Err(input.error("A table cannot be given as well as a `from` option."));
+                }
+
+                return Ok(TableSyntax {
+                    header: token::Bracket(from.span()),
+                    options,
+                    column_names: Punctuated::new(),
+                    test_inputs: vec![],
+                });
+            }
+            (Some(from), None) => return Err(syn::Error::new_spanned(
+                from,
+                "The `from` option needs a `count` option, giving the number of elements to test.",
+            )),
+            (None, Some(_)) => {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "The `count` option can only be used with the `from` option.",
+                ))
+            }
+            (None, None) => {}
+        }
+
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Expected a parameter table, such as `[ input, expected ] [ 1, 2 ]`.",
+            ));
+        }
+
+        if input.peek(kw::cross) {
+            input.parse::<kw::cross>()?;
+            let (header, mut column_names, mut test_inputs) = parse_table(input, &options)?;
+            check_row_lengths(&column_names, &test_inputs)?;
+
+            while input.peek(kw::with) {
+                input.parse::<kw::with>()?;
+                let (_, other_names, other_inputs) = parse_table(input, &options)?;
+                check_row_lengths(&other_names, &other_inputs)?;
+
+                test_inputs = test_inputs
+                    .iter()
+                    .flat_map(|row| other_inputs.iter().map(move |other| row.cross(other)))
+                    .collect();
+                column_names.extend(other_names);
+            }
+
+            return Ok(TableSyntax {
+                options,
+                header,
+                column_names,
+                test_inputs,
+            });
+        }
+
+        if input.peek(kw::matrix) {
+            input.parse::<kw::matrix>()?;
+            let (header, column_names, test_inputs) = parse_matrix(input, &options)?;
+            return Ok(TableSyntax {
+                options,
+                header,
+                column_names,
+                test_inputs,
+            });
+        }
+
+        if input.peek(kw::rows) {
+            input.parse::<kw::rows>()?;
+            let (header, column_names, test_inputs) = parse_rows(input, &options)?;
+            return Ok(TableSyntax {
+                options,
+                header,
+                column_names,
+                test_inputs,
+            });
+        }
+
+        let (header, column_names, test_inputs) = parse_table(input, &options)?;
+
+        Ok(TableSyntax {
+            options,
+            header,
+            column_names,
+            test_inputs,
+        })
+    }
+}
+
+/// A `use <name>` reference to a table declared with `parameter_table!`.
+struct SharedTableSyntax {
+    /// The options preceding `use`, which are passed along to the table.
+    options: proc_macro2::TokenStream,
+    table: Path,
+}
+
+impl Parse for SharedTableSyntax {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut options = proc_macro2::TokenStream::new();
+        while !input.peek(Token![use]) {
+            options.extend([input.parse::<proc_macro2::TokenTree>()?]);
+        }
+
+        input.parse::<Token![use]>()?;
+        let table = input.parse()?;
+
+        Ok(SharedTableSyntax { options, table })
+    }
+}
+
+/// The declaration of a table by `parameter_table!`.
+struct ParameterTableSyntax {
+    name: Ident,
+    table: proc_macro2::TokenStream,
+}
+
+impl Parse for ParameterTableSyntax {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        let table: proc_macro2::TokenStream = input.parse()?;
+        syn::parse2::<TableSyntax>(table.clone())?;
+
+        Ok(ParameterTableSyntax { name, table })
+    }
+}
+
+/// Parses a header followed by the rows which belong to it, stopping at the
+/// end of the input or at a `with` separating it from another table.
+fn parse_table(
+    input: ParseStream,
+    options: &Options,
+) -> Result<(token::Bracket, Punctuated<Column, Token![,]>, Vec<Row>)> {
+    let names_input;
+    let header = syn::bracketed!(names_input in input);
+    let header_tokens = names_input
+        .fork()
+        .parse::<proc_macro2::TokenStream>()?
+        .to_string();
+    let mut column_names = names_input.parse_terminated(Column::parse)?;
+    if column_names.is_empty() {
+        return Err(syn::Error::new(
+            header.span,
+            "This table has no columns. A test without parameters should use `#[test]` instead, with a loop if it should run more than once.",
+        ));
+    }
+
+    let is_last_table = !input
+        .fork()
+        .parse::<proc_macro2::TokenStream>()?
+        .into_iter()
+        .any(|token| matches!(token, proc_macro2::TokenTree::Ident(ident) if ident == "with"));
+    let platforms = platform_columns(&column_names);
+    // The flag is marked first, so that it is not taken as an expected column.
+    mark_panic_flag_column(&mut column_names, options);
+    if is_last_table {
+        mark_expected_column(&mut column_names, header.span, options)?;
+    }
+    check_platform_columns(&column_names, &platforms)?;
+
+    let mut name_columns = column_names
+        .iter()
+        .filter(|column| matches!(column, Column::CaseName(_)));
+    if let (Some(_), Some(column)) = (name_columns.next(), name_columns.next()) {
+        return Err(syn::Error::new_spanned(
+            column,
+            "Only one column may be marked with `name!`.",
+        ));
+    }
+    let mut check_columns = column_names
+        .iter()
+        .filter(|column| matches!(column, Column::Check(_)));
+    if let Some(column) = check_columns.next() {
+        let problem = if options.assert.is_some() {
+            Some((
+                column,
+                "A `check!` column cannot be used with the `assert` option.",
+            ))
+        } else {
+            check_columns
+                .next()
+                .map(|column| (column, "Only one column may be marked with `check!`."))
+        };
+        if let Some((column, message)) = problem {
+            return Err(syn::Error::new_spanned(column, message));
+        }
+    }
+
+    let mut test_inputs = vec![];
+    let mut position = 0;
+    while !input.is_empty() && !input.peek(kw::with) {
+        if input.peek(Token![,]) {
+            return Err(input.error(
+                "Rows are separated by placing them next to each other, not by commas. Remove this comma.",
+            ));
+        }
+        if is_repeated_header(input, &header_tokens) {
+            input.parse::<proc_macro2::TokenTree>()?;
+            continue;
+        }
+
+        let skip: Option<kw::skip> = input.parse()?;
+        let mut row = Row::parse(input, &column_names, position + 1)?;
+        select_platforms(&platforms, &mut row)?;
+        row.index = vec![position];
+        position += 1;
+        if skip.is_none() {
+            test_inputs.extend(row.expand_ranges(&column_names)?);
+        }
+    }
+
+    Ok((header, column_names, test_inputs))
+}
+
+/// Parses a header followed by a range for each of its columns, such as
+/// `[ a, b ] a in 0..3, b in 0..3`, and generates a row for every combination
+/// of their values.
+fn parse_matrix(
+    input: ParseStream,
+    options: &Options,
+) -> Result<(token::Bracket, Punctuated<Column, Token![,]>, Vec<Row>)> {
+    let names_input;
+    let header = syn::bracketed!(names_input in input);
+    let column_names = names_input.parse_terminated(Column::parse)?;
+    if column_names.is_empty() {
+        return Err(syn::Error::new(header.span, "This matrix has no columns."));
+    }
+    if options.assert.is_some() {
+        return Err(syn::Error::new(
+            header.span,
+            "A `matrix` cannot be used with the `assert` option, as it has no expected column.",
+        ));
+    }
+    let mut names = vec![];
+    for column in column_names.iter() {
+        match column {
+            Column::Parameter(Parameter {
+                name,
+                cells: Cells::Values,
+                default: None,
+                ..
+            }) => names.push(name),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    column,
+                    "Only plain columns can be used in a `matrix`, as each one is given a range.",
+                ))
+            }
+        }
+    }
+
+    let mut ranges: Vec<Option<Vec<Expr>>> = vec![None; names.len()];
+    loop {
+        let name: Ident = input.parse()?;
+        input.parse::<Token![in]>()?;
+        let range: Expr = input.parse()?;
+        match names.iter().position(|column| **column == name) {
+            Some(idx) if ranges[idx].is_some() => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("The `{}` column has already been given a range.", name),
+                ))
+            }
+            Some(idx) => ranges[idx] = Some(range_values(&range)?),
+            None => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("This matrix has no `{}` column.", name),
+                ))
+            }
+        }
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            break;
+        }
+    }
+    let ranges = names
+        .iter()
+        .zip(ranges)
+        .map(|(name, range)| {
+            range.ok_or_else(|| {
+                syn::Error::new(
+                    header.span,
+                    format!(
+                        "The `{}` column needs a range, such as `{} in 0..3`.",
+                        name, name
+                    ),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Each row is indexed by the position of each of its values in their
+    // range, so that the cases are named like those of crossed tables.
+    let mut test_inputs = vec![Row {
+        attrs: vec![],
+        index: vec![],
+        bracket: header,
+        args: Punctuated::new(),
+        source: None,
+    }];
+    for values in ranges {
+        test_inputs = test_inputs
+            .iter()
+            .flat_map(|row| {
+                values.iter().enumerate().map(move |(position, value)| {
+                    let mut row = row.clone();
+                    row.index.push(position);
+                    row.args.push(value.clone());
+                    row
+                })
+            })
+            .collect();
+    }
+
+    Ok((header, column_names, test_inputs))
+}
+
+/// Parses a block of rows separated by semicolons, each made of `column = cell`
+/// pairs, such as `rows { input = 1 expected = 2; input = 2 expected = 4 }`.
+/// The block is rewritten as a table of braced rows, whose header has the
+/// columns of the first row in order.
+fn parse_rows(
+    input: ParseStream,
+    options: &Options,
+) -> Result<(token::Bracket, Punctuated<Column, Token![,]>, Vec<Row>)> {
+    use proc_macro2::{Delimiter, Group, TokenTree};
+
+    let content;
+    let brace = syn::braced!(content in input);
+    let tokens: Vec<TokenTree> = content
+        .parse::<proc_macro2::TokenStream>()?
+        .into_iter()
+        .collect();
+    let is_semi =
+        |token: &TokenTree| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ';');
+
+    let mut header = None;
+    let mut rows = proc_macro2::TokenStream::new();
+    for row in tokens.split(is_semi).filter(|row| !row.is_empty()) {
+        // The attributes before a row are kept with it.
+        let mut start = 0;
+        while let [TokenTree::Punct(pound), TokenTree::Group(group), ..] = &row[start..] {
+            if pound.as_char() != '#' || group.delimiter() != Delimiter::Bracket {
+                break;
+            }
+            start += 2;
+        }
+        let (attrs, row) = row.split_at(start);
+        let keys = row_keys(row);
+        if keys.first().map(|(key, _)| *key) != Some(0) {
+            let span = row.first().map_or(brace.span, TokenTree::span);
+            return Err(syn::Error::new(
+                span,
+                "Expected a `column = cell` pair, such as `input = 1`.",
+            ));
+        }
+
+        let mut names = Vec::with_capacity(keys.len());
+        let mut pairs = Vec::with_capacity(keys.len());
+        for (idx, &(key, eq)) in keys.iter().enumerate() {
+            let end = keys.get(idx + 1).map_or(row.len(), |(next, _)| *next);
+            names.push(
+                row[key..eq]
+                    .iter()
+                    .cloned()
+                    .collect::<proc_macro2::TokenStream>(),
+            );
+            pairs.push(
+                row[key..end]
+                    .iter()
+                    .cloned()
+                    .collect::<proc_macro2::TokenStream>(),
+            );
+        }
+        header.get_or_insert(names);
+
+        let mut group = Group::new(Delimiter::Brace, quote!(#(#pairs),*));
+        group.set_span(row[0].span());
+        rows.extend(attrs.iter().cloned());
+        rows.extend([TokenTree::Group(group)]);
+    }
+
+    let header = match header {
+        Some(header) => header,
+        None => return Err(syn::Error::new(brace.span, "This block has no rows.")),
+    };
+    let mut header = Group::new(Delimiter::Bracket, quote!(#(#header),*));
+    header.set_span(brace.span);
+    (|input: ParseStream| parse_table(input, options)).parse2(quote!(#header #rows))
+}
+
+/// Finds the start of each `column = cell` pair in a row of a `rows` block,
+/// along with the position of its `=`. A pair starts at a column name, or a
+/// marker such as `name!`, which is followed by a `=` that is not part of
+/// `==` or `=>`.
+fn row_keys(row: &[proc_macro2::TokenTree]) -> Vec<(usize, usize)> {
+    use proc_macro2::{Spacing, TokenTree};
+
+    let is_eq = |idx: usize| match (row.get(idx), row.get(idx + 1)) {
+        (Some(TokenTree::Punct(eq)), next) if eq.as_char() == '=' => {
+            eq.spacing() == Spacing::Alone
+                || !matches!(next, Some(TokenTree::Punct(next)) if matches!(next.as_char(), '=' | '>'))
+        }
+        _ => false,
+    };
+    let mut keys = vec![];
+    for (idx, token) in row.iter().enumerate() {
+        if let TokenTree::Ident(_) = token {
+            if is_eq(idx + 1) {
+                keys.push((idx, idx + 1));
+            } else if matches!(row.get(idx + 1), Some(TokenTree::Punct(bang)) if bang.as_char() == '!' && bang.spacing() == Spacing::Alone)
+                && is_eq(idx + 2)
+            {
+                keys.push((idx, idx + 2));
+            }
+        }
+    }
+    keys
+}
+
+/// Whether the next row is a copy of the header, which is used to divide up a
+/// long table and does not generate a case.
+fn is_repeated_header(input: ParseStream, header: &str) -> bool {
+    let row = |input: ParseStream| {
+        let content;
+        syn::bracketed!(content in input);
+        content.parse::<proc_macro2::TokenStream>()
+    };
+    matches!(row(&input.fork()), Ok(row) if row.to_string() == header)
+}
+
+/// Which of the columns are `platform!` columns. This is found before the
+/// columns are marked, as a `platform!` column may become the column checked by
+/// the `assert` option.
+fn platform_columns(column_names: &Punctuated<Column, Token![,]>) -> Vec<bool> {
+    column_names
+        .iter()
+        .map(|column| {
+            matches!(
+                column,
+                Column::Parameter(Parameter {
+                    cells: Cells::Platforms,
+                    ..
+                })
+            )
+        })
+        .collect()
+}
+
+/// Checks that no `platform!` column holds patterns, whose alternatives are
+/// also separated by `|`.
+fn check_platform_columns(
+    column_names: &Punctuated<Column, Token![,]>,
+    platforms: &[bool],
+) -> Result<()> {
+    for (column, _) in column_names
+        .iter()
+        .zip(platforms)
+        .filter(|(_, platform)| **platform)
+    {
+        if let Column::Pattern(_) = column {
+            return Err(syn::Error::new_spanned(
+                column,
+                "A `platform!` column cannot hold patterns, as `|` separates their alternatives.",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Turns each cell of a `platform!` column which is written as
+/// `other | windows` into an expression which picks the value for the platform
+/// being compiled for. Cells without a `|` are used on every platform.
+fn select_platforms(platforms: &[bool], row: &mut Row) -> Result<()> {
+    let cells = row.args.iter_mut().zip(platforms);
+    for (cell, _) in cells.filter(|(_, platform)| **platform) {
+        if let Expr::Binary(syn::ExprBinary {
+            left,
+            op: syn::BinOp::BitOr(_),
+            right,
+            ..
+        }) = cell
+        {
+            if let Expr::Binary(syn::ExprBinary {
+                op: syn::BinOp::BitOr(_),
+                ..
+            }) = &**left
+            {
+                return Err(syn::Error::new_spanned(
+                    cell,
+                    "A `platform!` cell can only have two values, one for Windows after the `|`, such as `\"\\n\" | \"\\r\\n\"`.",
+                ));
+            }
+            *cell = syn::parse_quote!(if cfg!(windows) { #right } else { #left });
+        }
+    }
+    Ok(())
+}
+
+/// Converts the column named by the `panic_flag` option, if the table has it,
+/// into the column which decides whether each case should panic.
+fn mark_panic_flag_column(column_names: &mut Punctuated<Column, Token![,]>, options: &Options) {
+    let flag = match &options.panic_flag {
+        Some(flag) => flag,
+        None => return,
+    };
+    for column in column_names.iter_mut() {
+        if let Column::Parameter(Parameter { name, .. }) = column {
+            if name == flag {
+                *column = Column::PanicFlag(name.clone());
+            }
+        }
+    }
+}
+
+/// Whether a cell in the `panic_flag` column says that its case should panic,
+/// or `None` if it is not a `true` or `false` literal.
+fn panic_flag(cell: &Expr) -> Option<bool> {
+    match cell {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Bool(flag),
+            ..
+        }) => Some(flag.value),
+        _ => None,
+    }
+}
+
+/// Converts the last parameter columns of the table into the columns which are
+/// checked by the `assert` option, if it has been given. There is one column
+/// unless the `expected` option gives another number.
+fn mark_expected_column(
+    column_names: &mut Punctuated<Column, Token![,]>,
+    header: Span,
+    options: &Options,
+) -> Result<()> {
+    let assertion = match (&options.assert, options.expected) {
+        (Some(assertion), _) => assertion,
+        (None, None) => return Ok(()),
+        (None, Some(_)) => {
+            return Err(syn::Error::new(
+                header,
+                "The `expected` option can only be used with the `assert` option.",
+            ))
+        }
+    };
+
+    if let Assertion::Err = assertion {
+        let conflict = first_conflict(&[
+            (
+                options.expected.is_some(),
+                "the `expected` option, as an error has one message",
+            ),
+            (
+                options.no_std,
+                "the `no_std` option, as it formats the message of the error",
+            ),
+        ]);
+        if let Some(conflict) = conflict {
+            return Err(syn::Error::new(
+                header,
+                format!("`assert = err` cannot be used with {}.", conflict),
+            ));
+        }
+    }
+    if let Assertion::NoPanic = assertion {
+        let conflict = first_conflict(&[
+            (
+                options.expected.is_some(),
+                "the `expected` option, as there is no column to check",
+            ),
+            (
+                options.no_std,
+                "the `no_std` option, as it catches the panic",
+            ),
+        ]);
+        return match conflict {
+            Some(conflict) => Err(syn::Error::new(
+                header,
+                format!("`assert = no_panic` cannot be used with {}.", conflict),
+            )),
+            None => Ok(()),
+        };
+    }
+
+    let count = options.expected.unwrap_or(1);
+    let columns: Vec<_> = column_names
+        .iter_mut()
+        .rev()
+        .filter(|column| matches!(column, Column::Parameter(_)))
+        .take(count)
+        .collect();
+    if columns.len() < count {
+        let message = match count {
+            1 => "The `assert` option needs a column to check the test function's value against."
+                .to_string(),
+            count => format!(
+                "The `expected` option needs {} columns to check the test function's value against.",
+                count
+            ),
+        };
+        return Err(syn::Error::new(header, message));
+    }
+
+    for column in columns {
+        if let Column::Parameter(Parameter { ty: Some(ty), .. }) = column {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "This column is checked against the test function's value, so it cannot be given a type.",
+            ));
+        }
+        if let Column::Parameter(Parameter { name, .. }) = column {
+            *column = match assertion {
+                Assertion::Matches => Column::Pattern(name.clone()),
+                Assertion::Eq => Column::Expected(name.clone()),
+                Assertion::Err => Column::ErrorMessage(name.clone()),
+                // This returns above, as it marks no columns.
+                Assertion::NoPanic => unreachable!(),
+            };
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a table from a CSV file, in which the first line is the header and
+/// each following line is a row. Each line is parsed in the same way as the
+/// contents of a bracketed row, so commas inside brackets or string literals do
+/// not separate cells.
+fn parse_csv(
+    path: &LitStr,
+    options: &Options,
+) -> Result<(token::Bracket, Punctuated<Column, Token![,]>, Vec<Row>)> {
+    let contents = std::fs::read_to_string(resolve_path(path)).map_err(|error| {
+        syn::Error::new(
+            path.span(),
+            format!("Could not read `{}`: {}.", path.value(), error),
+        )
+    })?;
+
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let line_error = |number: usize, error: syn::Error| {
+        syn::Error::new(
+            path.span(),
+            format!("Line {} of `{}`: {}", number + 1, path.value(), error),
+        )
+    };
+
+    let (number, header_line) = lines
+        .next()
+        .ok_or_else(|| syn::Error::new(path.span(), format!("`{}` is empty.", path.value())))?;
+    let mut column_names = Punctuated::<Column, Token![,]>::parse_terminated
+        .parse_str(header_line)
+        .map_err(|error| line_error(number, error))?;
+    let platforms = platform_columns(&column_names);
+    mark_panic_flag_column(&mut column_names, options);
+    mark_expected_column(&mut column_names, path.span(), options)?;
+    check_platform_columns(&column_names, &platforms).map_err(|error| line_error(number, error))?;
+
+    let mut test_inputs = vec![];
+    for (position, (number, line)) in lines.enumerate() {
+        let args = (|input: ParseStream| parse_cells(input, &column_names, position + 1))
+            .parse_str(line)
+            .map_err(|error| line_error(number, error))?;
+        let mut row = Row {
+            attrs: vec![],
+            index: vec![position],
+            bracket: token::Bracket(path.span()),
+            args,
+            source: Some(format!("line {} of `{}`", number + 1, path.value())),
+        };
+        select_platforms(&platforms, &mut row).map_err(|error| line_error(number, error))?;
+        test_inputs.extend(
+            row.expand_ranges(&column_names)
+                .map_err(|error| line_error(number, error))?,
+        );
+    }
+
+    Ok((token::Bracket(path.span()), column_names, test_inputs))
+}
+
+/// Resolves a path relative to the root of the crate being compiled.
+fn resolve_path(path: &LitStr) -> std::path::PathBuf {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    std::path::Path::new(&root).join(path.value())
+}
+
+/// Checks that every row in a table has one cell for each of its columns.
+fn check_row_lengths(column_names: &Punctuated<Column, Token![,]>, rows: &[Row]) -> Result<()> {
+    let mut errors = Errors::default();
+    for row in rows {
+        if let Some(error) = row_length_error(column_names, row) {
+            errors.push(error);
+        }
+    }
+    errors.finish()
+}
+
+/// The `key = value` and flag options which may precede the parameter table.
+#[derive(Default)]
+struct Options {
+    /// Whether to generate the cases inside a module named after the test.
+    module: bool,
+    /// Attributes which are applied to the module generated by the `module`
+    /// option.
+    mod_attrs: Option<Vec<Meta>>,
+    /// Whether to generate a single test which runs every case in a loop.
+    single_test: bool,
+    /// Whether the cases of a `single_test` table are also generated as
+    /// separate tests, for `single_test = both`.
+    keep_cases: bool,
+    /// Whether rows which are the same as an earlier row are allowed.
+    allow_duplicates: bool,
+    /// Whether to compile everything which is generated only for tests.
+    cfg_test: bool,
+    /// Whether to declare a constant holding the number of cases.
+    expose_count: bool,
+    /// Whether to check that the cells of each column have the same type.
+    homogeneous: bool,
+    /// Whether each case holds a lock shared with the other cases, so that they
+    /// do not run at the same time.
+    serial: bool,
+    /// Whether an attribute stacked above this one uses the lock of the
+    /// `serial` option, which is declared by the last attribute in the stack.
+    serial_above: bool,
+    /// Whether an attribute stacked above this one generates its cases in the
+    /// module named after the test function.
+    module_above: bool,
+    /// Whether an attribute stacked above this one generates the single test
+    /// named after the test function, outside of a module.
+    single_test_above: bool,
+    /// Whether to declare a function returning the names of the cases.
+    expose_names: bool,
+    /// Whether to add the table to the documentation of the test function.
+    doc_table: bool,
+    /// Whether to also check each case when the crate is compiled, by calling
+    /// the test function in a constant.
+    const_check: bool,
+    /// Whether to pass the number of each case's row to the test function,
+    /// after its name.
+    expose_index: bool,
+    /// Whether to copy the body of the test function into each case, rather
+    /// than calling it.
+    inline: bool,
+    /// Whether to generate a benchmark for each case, rather than a test.
+    bench: bool,
+    /// Whether to generate only code which can be compiled without `std`.
+    no_std: bool,
+    /// Whether to add the cells of a case's row to its panic message.
+    show_cells: bool,
+    /// Whether to generate the cases in the order of their names, rather than
+    /// the order of their rows.
+    sorted: bool,
+    /// Whether each column must have the same name as its parameter.
+    strict: bool,
+    /// Whether to pass each case a temporary directory in place of a fixture.
+    tempdir: bool,
+    /// Whether to generate a `#[test_case]` attribute for each row, rather
+    /// than the cases themselves.
+    test_case: bool,
+    /// Whether to pass the name of each case to the test function as its
+    /// first argument.
+    case_name: bool,
+    /// The attribute applied to each case in place of `#[test]`, with any
+    /// arguments it was given.
+    test_attr: Option<TestAttr>,
+    /// A CSV file, relative to the crate root, from which to read the table.
+    csv: Option<LitStr>,
+    /// The feature of the crate which must be enabled for the cases to be
+    /// compiled.
+    if_cfg: Option<LitStr>,
+    /// How each case should check the value returned by the test function.
+    assert: Option<Assertion>,
+    /// The column whose cells decide whether each case should panic.
+    panic_flag: Option<Ident>,
+    /// The visibility and name to give the test function, so that its own name
+    /// is free to be used by other items.
+    helper: Option<(Visibility, Ident)>,
+    /// The function which the cases call, in place of the test function.
+    call: Option<Path>,
+    /// The file of recorded values which each case compares its value with.
+    golden: Option<LitStr>,
+    /// A function whose result each case compares with the test function's,
+    /// when both are called with the cells of the row.
+    reference: Option<Path>,
+    /// An expression of the columns which each case checks, in place of
+    /// calling a function.
+    expr: Option<Expr>,
+    /// Whether to leave the test function out of the expansion, as the cases
+    /// call the `call` function instead.
+    no_helper: bool,
+    /// An expression which is evaluated for each case and passed to the test
+    /// function as its first argument.
+    setup: Option<Expr>,
+    /// Functions which are called for each case, whose results are passed to
+    /// the test function after the fixture.
+    fixtures: Option<Vec<Ident>>,
+    /// An expression which is evaluated at the end of each case, even if it
+    /// panics.
+    teardown: Option<Expr>,
+    /// The number of times to run each case.
+    repeat: Option<usize>,
+    /// The generic arguments passed to the test function by cases which have no
+    /// `ty!` or `const!` columns.
+    types: Option<AngleBracketedGenericArguments>,
+    /// The number of cases above which a warning suggests `single_test`.
+    max_cases: Option<usize>,
+    /// The number of columns checked by the `assert` option.
+    expected: Option<usize>,
+    /// An array whose elements are used as the rows of the table.
+    from: Option<Expr>,
+    /// The number of elements of the `from` array.
+    count: Option<usize>,
+    /// How to name cases when the table has no `name!` column.
+    naming: Option<Naming>,
+    /// Which cases to ignore when the tests are run under Miri.
+    miri: Option<Miri>,
+    /// The template from which the whole name of each case is made, in place
+    /// of the name of the test function followed by the case's stem.
+    name_template: Option<LitStr>,
+    /// The number to start counting cases from.
+    start: Option<usize>,
+    /// The group which the cases belong to, which is added to their names
+    /// before the `prefix`.
+    group: Option<String>,
+    /// Text added to the start of the name of every case, after the name of
+    /// the test function.
+    prefix: Option<String>,
+    /// Text added to the end of the name of every case.
+    suffix: Option<String>,
+    /// The number of rows in the tables of the attributes stacked above this
+    /// one, which are counted before its own rows.
+    offset: usize,
+    /// The names of the cases generated by the attributes stacked above this
+    /// one, each with the `#[cfg]` attributes it is compiled under.
+    cases_above: Vec<(Vec<Attribute>, LitStr)>,
+    /// The width to pad case numbers to with zeroes, or `Some(None)` to pad
+    /// them to the width of the largest number.
+    pad: Option<Option<usize>>,
+    /// The keys of the options which were given, so that errors about an
+    /// option can point at it.
+    keys: Vec<Ident>,
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut options = Options::default();
+        parse_passed_on(input, &mut options)?;
+
+        while input.peek(Ident)
+            && !input.peek(kw::cross)
+            && !input.peek(kw::matrix)
+            && !input.peek(kw::rows)
+        {
+            let key: Ident = input.parse()?;
+            options.keys.push(key.clone());
+
+            if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                match key.to_string().as_str() {
+                    "attr" | "runtime" => set_option(&mut options.test_attr, &key, input.parse()?)?,
+                    "csv" => set_option(&mut options.csv, &key, input.parse()?)?,
+                    "if_cfg" => set_option(&mut options.if_cfg, &key, input.parse()?)?,
+                    "assert" => set_option(&mut options.assert, &key, input.parse()?)?,
+                    "panic_flag" => set_option(&mut options.panic_flag, &key, input.parse()?)?,
+                    "helper" => {
+                        set_option(&mut options.helper, &key, (input.parse()?, input.parse()?))?
+                    }
+                    "call" => set_option(&mut options.call, &key, input.parse()?)?,
+                    "reference" => set_option(&mut options.reference, &key, input.parse()?)?,
+                    "golden" if cfg!(feature = "golden") => {
+                        set_option(&mut options.golden, &key, input.parse()?)?
+                    }
+                    "golden" => return Err(syn::Error::new(
+                        key.span(),
+                        "The `golden` option needs the `golden` feature of `test_with_parameters`.",
+                    )),
+                    "expr" => set_option(&mut options.expr, &key, input.parse()?)?,
+                    "setup" => set_option(&mut options.setup, &key, input.parse()?)?,
+                    "with_context" => {
+                        let constructor: Path = input.parse()?;
+                        set_option(&mut options.setup, &key, syn::parse_quote!(#constructor()))?
+                    }
+                    "mod_attrs" => {
+                        set_option(&mut options.mod_attrs, &key, parse_mod_attrs(input)?)?
+                    }
+                    "fixtures" => {
+                        set_option(&mut options.fixtures, &key, parse_fixtures(input, &key)?)?
+                    }
+                    "teardown" => set_option(&mut options.teardown, &key, input.parse()?)?,
+                    "repeat" => set_option(&mut options.repeat, &key, parse_count(input, &key)?)?,
+                    "types" => set_option(&mut options.types, &key, input.parse()?)?,
+                    "max_cases" => {
+                        set_option(&mut options.max_cases, &key, parse_count(input, &key)?)?
+                    }
+                    "expected" => {
+                        set_option(&mut options.expected, &key, parse_count(input, &key)?)?
+                    }
+                    "from" => set_option(&mut options.from, &key, input.parse()?)?,
+                    "count" => set_option(&mut options.count, &key, parse_count(input, &key)?)?,
+                    "naming" => set_option(&mut options.naming, &key, input.parse()?)?,
+                    "single_test" => {
+                        let mode: Ident = input.parse()?;
+                        if mode != "both" {
+                            return Err(syn::Error::new(
+                                mode.span(),
+                                "Unknown `single_test` mode. Expected `both`.",
+                            ));
+                        }
+                        set_flag(&mut options.single_test, &key)?;
+                        options.keep_cases = true;
+                    }
+                    "miri" => set_option(&mut options.miri, &key, input.parse()?)?,
+                    "name_template" => set_option(
+                        &mut options.name_template,
+                        &key,
+                        parse_name_template(input)?,
+                    )?,
+                    "start" => set_option(&mut options.start, &key, parse_usize(input)?)?,
+                    "group" => set_option(&mut options.group, &key, parse_affix(input, &key)?)?,
+                    "prefix" => set_option(&mut options.prefix, &key, parse_affix(input, &key)?)?,
+                    "suffix" => set_option(&mut options.suffix, &key, parse_affix(input, &key)?)?,
+                    "pad" => set_option(&mut options.pad, &key, Some(parse_usize(input)?))?,
+                    _ => return Err(syn::Error::new(key.span(), "Unknown option.")),
+                }
+            } else {
+                match key.to_string().as_str() {
+                    "module" => set_flag(&mut options.module, &key)?,
+                    "single_test" => set_flag(&mut options.single_test, &key)?,
+                    "allow_duplicates" => set_flag(&mut options.allow_duplicates, &key)?,
+                    "expose_count" => set_flag(&mut options.expose_count, &key)?,
+                    "homogeneous" => set_flag(&mut options.homogeneous, &key)?,
+                    "serial" => set_flag(&mut options.serial, &key)?,
+                    "expose_names" => set_flag(&mut options.expose_names, &key)?,
+                    "doc_table" => set_flag(&mut options.doc_table, &key)?,
+                    "const_check" => set_flag(&mut options.const_check, &key)?,
+                    "cfg_test" => set_flag(&mut options.cfg_test, &key)?,
+                    "case_name" => set_flag(&mut options.case_name, &key)?,
+                    "inline" => set_flag(&mut options.inline, &key)?,
+                    "no_helper" => set_flag(&mut options.no_helper, &key)?,
+                    "no_std" => set_flag(&mut options.no_std, &key)?,
+                    "show_cells" => set_flag(&mut options.show_cells, &key)?,
+                    "sorted" => set_flag(&mut options.sorted, &key)?,
+                    "strict" => set_flag(&mut options.strict, &key)?,
+                    "tempdir" if cfg!(feature = "tempfile") => {
+                        set_flag(&mut options.tempdir, &key)?
+                    }
+                    "tempdir" => return Err(syn::Error::new(
+                        key.span(),
+                        "The `tempdir` option needs the `tempfile` feature of `test_with_parameters`.",
+                    )),
+                    "test_case" if cfg!(feature = "test_case") => {
+                        set_flag(&mut options.test_case, &key)?
+                    }
+                    "test_case" => return Err(syn::Error::new(
+                        key.span(),
+                        "The `test_case` option needs the `test_case` feature of `test_with_parameters`.",
+                    )),
+                    "bench" if cfg!(feature = "bench") => set_flag(&mut options.bench, &key)?,
+                    "bench" => return Err(syn::Error::new(
+                        key.span(),
+                        "The `bench` option needs the `bench` feature of `test_with_parameters`.",
+                    )),
+                    "expose_index" => set_flag(&mut options.expose_index, &key)?,
+                    "pad" => set_option(&mut options.pad, &key, None)?,
+                    _ => return Err(syn::Error::new(key.span(), "Unknown option.")),
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        // An expression is checked with `assert_eq!` unless told otherwise.
+        if options.expr.is_some() && options.assert.is_none() {
+            options.assert = Some(Assertion::Eq);
+        }
+
+        Ok(options)
+    }
+}
+
+/// The ways in which a case can check the value returned by the test function,
+/// using the last column of the table.
+enum Assertion {
+    /// `assert = matches`, where the last column holds a pattern which the
+    /// value must match.
+    Matches,
+    /// `assert = eq`, where the last column holds the value which the value
+    /// returned by the test function must equal.
+    Eq,
+    /// `assert = err`, where the last column holds text which the message of
+    /// the error returned by the test function must contain, or `None` if it
+    /// must return `Ok`.
+    Err,
+    /// `assert = no_panic`, where there is no column to check, and each case
+    /// fails with its own message if the test function panics.
+    NoPanic,
+}
+
+impl Parse for Assertion {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mode: Ident = input.parse()?;
+        match mode.to_string().as_str() {
+            "matches" => Ok(Assertion::Matches),
+            "eq" => Ok(Assertion::Eq),
+            "err" => Ok(Assertion::Err),
+            "no_panic" => Ok(Assertion::NoPanic),
+            _ => Err(syn::Error::new(
+                mode.span(),
+                "Unknown assertion. Expected `matches`, `eq`, `err` or `no_panic`.",
+            )),
+        }
+    }
+}
+
+/// The ways in which cases can be named when the table has no `name!` column.
+enum Naming {
+    /// `naming = index`, where each case is named after its position in the
+    /// table.
+    Index,
+    /// `naming = hash`, where each case is named after a hash of its cells.
+    Hash,
+}
+
+impl Parse for Naming {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mode: Ident = input.parse()?;
+        match mode.to_string().as_str() {
+            "index" => Ok(Naming::Index),
+            "hash" => Ok(Naming::Hash),
+            _ => Err(syn::Error::new(
+                mode.span(),
+                "Unknown naming. Expected `index` or `hash`.",
+            )),
+        }
+    }
+}
+
+/// The cases which are ignored when the tests are run under Miri.
+enum Miri {
+    /// `miri = skip_slow`, where the cases of rows tagged `slow` are ignored.
+    SkipSlow,
+}
+
+impl Parse for Miri {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mode: Ident = input.parse()?;
+        match mode.to_string().as_str() {
+            "skip_slow" => Ok(Miri::SkipSlow),
+            _ => Err(syn::Error::new(
+                mode.span(),
+                "Unknown Miri mode. Expected `skip_slow`.",
+            )),
+        }
+    }
+}
+
+/// The attribute which marks each case, such as `test` or
+/// `tokio::test(flavor = "multi_thread")`.
+struct TestAttr {
+    path: Path,
+    /// The arguments of the attribute, which are passed on as they are written.
+    args: Option<proc_macro2::TokenStream>,
+}
+
+impl Parse for TestAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        let args = if input.peek(token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            Some(content.parse()?)
+        } else {
+            None
+        };
+        Ok(TestAttr { path, args })
+    }
+}
+
+impl ToTokens for TestAttr {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.path.to_tokens(tokens);
+        if let Some(args) = &self.args {
+            tokens.extend(quote! { (#args) });
+        }
+    }
+}
+
+impl Options {
+    /// The key of the given option, if it was given.
+    fn key(&self, name: &str) -> Option<&Ident> {
+        self.keys.iter().find(|key| *key == name)
+    }
+
+    /// The name given to the test function in the expansion.
+    fn helper_name<'a>(&'a self, test_fn: &'a ItemFn) -> &'a Ident {
+        self.helper
+            .as_ref()
+            .map_or(&test_fn.sig.ident, |(_, helper)| helper)
+    }
+
+    /// The number of parameters of the test function which come before the
+    /// columns of the table.
+    fn implicit_count(&self) -> usize {
+        usize::from(self.case_name)
+            + usize::from(self.expose_index)
+            + usize::from(self.setup.is_some() || self.tempdir)
+            + self.fixtures.as_ref().map_or(0, Vec::len)
+    }
+
+    /// The turbofish given by the `types` option, if it was used.
+    fn turbofish(&self) -> Option<proc_macro2::TokenStream> {
+        let types = &self.types.as_ref()?.args;
+        Some(quote!(::<#types>))
+    }
+
+    /// Adds the `group`, `prefix` and `suffix` options to the stem of a case's
+    /// name.
+    fn affixed_stem(&self, stem: &str) -> String {
+        let group = self.group.as_ref().map(|group| format!("{}_", group));
+        format!(
+            "{}{}{}{}",
+            group.unwrap_or_default(),
+            self.prefix.as_deref().unwrap_or_default(),
+            stem,
+            self.suffix.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// The name of the function of a case with the given stem, which is the
+    /// stem itself when it was made by the `name_template` option.
+    fn case_fn_name(&self, test_fn: &ItemFn, stem: &str) -> String {
+        match self.name_template {
+            Some(_) => stem.to_owned(),
+            None => format!("{}_{}", test_fn.sig.ident, stem),
+        }
+    }
+
+    /// The number given to the first row of the table. In a stacked table, the
+    /// `start` option replaces the numbering carried on from the tables above.
+    fn first_index(&self) -> usize {
+        self.start.unwrap_or(self.offset)
+    }
+
+    /// The path of the function which the cases call, from where the cases
+    /// are generated.
+    fn call_target(&self, test_fn: &ItemFn) -> proc_macro2::TokenStream {
+        // The module generated by the `module` option imports everything from
+        // its parent, so a `call` path resolves inside it as it does outside.
+        match (&self.call, self.module) {
+            (Some(call), _) => quote!(#call),
+            (None, true) => {
+                let helper = self.helper_name(test_fn);
+                quote!(super::#helper)
+            }
+            (None, false) => {
+                let helper = self.helper_name(test_fn);
+                quote!(#helper)
+            }
+        }
+    }
+}
+
+/// Parses an integer literal as a `usize`.
+fn parse_usize(input: ParseStream) -> Result<usize> {
+    input.parse::<LitInt>()?.base10_parse()
+}
+
+/// Parses the bracketed list of attributes given to the `mod_attrs` option,
+/// which are written without their `#[...]`.
+fn parse_mod_attrs(input: ParseStream) -> Result<Vec<Meta>> {
+    let content;
+    syn::bracketed!(content in input);
+    let attrs = Punctuated::<Meta, Token![,]>::parse_terminated(&content)?;
+    Ok(attrs.into_iter().collect())
+}
+
+/// Parses the bracketed list of functions given to the `fixtures` option,
+/// failing if it is empty.
+fn parse_fixtures(input: ParseStream, key: &Ident) -> Result<Vec<Ident>> {
+    let content;
+    let bracket = syn::bracketed!(content in input);
+    let fixtures = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+    if fixtures.is_empty() {
+        return Err(syn::Error::new(
+            bracket.span,
+            format!("The `{}` option needs at least one fixture.", key),
+        ));
+    }
+    Ok(fixtures.into_iter().collect())
+}
+
+/// Parses a string literal which is added to the names of cases, failing if it
+/// could not be part of an identifier.
+fn parse_affix(input: ParseStream, key: &Ident) -> Result<String> {
+    let lit: LitStr = input.parse()?;
+    let affix = lit.value();
+    if affix.is_empty() || syn::parse_str::<Ident>(&format!("x{}", affix)).is_err() {
+        return Err(syn::Error::new(
+            lit.span(),
+            format!(
+                "The `{}` option must be made of characters which can be used in an identifier, such as `fast_`.",
+                key
+            ),
+        ));
+    }
+    Ok(affix)
+}
+
+/// The placeholders which can be used in the `name_template` option.
+const PLACEHOLDERS: &[&str] = &["{fn}", "{index}", "{name_column}"];
+
+/// Wraps the options which a stacked attribute passes on to the one below it in
+/// a group without delimiters. Such a group cannot be written in source code,
+/// so these options cannot be given by hand.
+fn passed_on(options: proc_macro2::TokenStream) -> proc_macro2::Group {
+    proc_macro2::Group::new(proc_macro2::Delimiter::None, options)
+}
+
+/// Parses the options passed on to this attribute by the one stacked above it,
+/// if there are any.
+fn parse_passed_on(input: ParseStream, options: &mut Options) -> Result<()> {
+    while let Some((inside, _, _)) = input.cursor().group(proc_macro2::Delimiter::None) {
+        let passed_on = inside.token_stream();
+        input.step(|cursor| match cursor.group(proc_macro2::Delimiter::None) {
+            Some((_, _, rest)) => Ok(((), rest)),
+            None => Err(cursor.error("Expected the options of a stacked attribute.")),
+        })?;
+        let parser = |input: ParseStream| {
+            while !input.is_empty() {
+                let key: Ident = input.parse()?;
+                match key.to_string().as_str() {
+                    "offset" => {
+                        input.parse::<Token![=]>()?;
+                        options.offset = parse_usize(input)?;
+                    }
+                    "cases" => {
+                        input.parse::<Token![=]>()?;
+                        options.cases_above = parse_cases_above(input)?;
+                    }
+                    "serial" => options.serial_above = true,
+                    "module" => options.module_above = true,
+                    "single_test" => options.single_test_above = true,
+                    _ => return Err(syn::Error::new(key.span(), "Unknown option.")),
+                }
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            Ok(())
+        };
+        parser.parse2(passed_on)?;
+        input.parse::<Token![,]>()?;
+    }
+    Ok(())
+}
+
+/// Parses the names of the cases passed on by a stacked attribute, each with the
+/// `#[cfg]` attributes it is compiled under.
+fn parse_cases_above(input: ParseStream) -> Result<Vec<(Vec<Attribute>, LitStr)>> {
+    let content;
+    syn::bracketed!(content in input);
+    let mut cases = vec![];
+    while !content.is_empty() {
+        cases.push((content.call(Attribute::parse_outer)?, content.parse()?));
+        if !content.is_empty() {
+            content.parse::<Token![,]>()?;
+        }
+    }
+    Ok(cases)
+}
+
+/// Parses the template of the `name_template` option, failing if it uses an
+/// unknown placeholder or has no placeholder which tells the cases apart.
+fn parse_name_template(input: ParseStream) -> Result<LitStr> {
+    let lit: LitStr = input.parse()?;
+    let template = lit.value();
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find(['{', '}']) {
+        rest = &rest[start..];
+        match PLACEHOLDERS
+            .iter()
+            .find(|placeholder| rest.starts_with(*placeholder))
+        {
+            Some(placeholder) => rest = &rest[placeholder.len()..],
+            None => {
+                let end = rest.find('}').map_or(rest.len(), |end| end + 1);
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!(
+                        "Unknown placeholder `{}`. Expected `{{fn}}`, `{{index}}` or `{{name_column}}`.",
+                        &rest[..end]
+                    ),
+                ));
+            }
+        }
+    }
+    if !template.contains("{index}") && !template.contains("{name_column}") {
+        return Err(syn::Error::new(
+            lit.span(),
+            "The `name_template` option must use `{index}` or `{name_column}`, so that each case has its own name.",
+        ));
+    }
+    Ok(lit)
+}
+
+/// Fills in the placeholders of the `name_template` option for one case,
+/// failing if the name which it gives is not an identifier.
+fn templated_name(template: &LitStr, test_fn: &ItemFn, index: &str, names: &str) -> Result<String> {
+    let name = template
+        .value()
+        .replace("{fn}", &test_fn.sig.ident.to_string())
+        .replace("{index}", index)
+        .replace("{name_column}", names);
+    match syn::parse_str::<Ident>(&name) {
+        Ok(_) => Ok(name),
+        Err(_) => Err(syn::Error::new(
+            template.span(),
+            format!(
+                "The `name_template` option gives a case the name `{}`, which is not an identifier.",
+                name
+            ),
+        )),
+    }
+}
+
+/// Parses an integer literal as a `usize`, failing if it is zero.
+fn parse_count(input: ParseStream, key: &Ident) -> Result<usize> {
+    let lit: LitInt = input.parse()?;
+    match lit.base10_parse()? {
+        0 => Err(syn::Error::new(
+            lit.span(),
+            format!("The `{}` option must be at least 1.", key),
+        )),
+        count => Ok(count),
+    }
+}
+
+/// Collects errors so that several of them can be reported together.
+#[derive(Default)]
+struct Errors(Option<syn::Error>);
+
+impl Errors {
+    fn push(&mut self, error: syn::Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    fn extend(&mut self, errors: impl IntoIterator<Item = syn::Error>) {
+        errors.into_iter().for_each(|error| self.push(error));
+    }
+
+    /// Fails with every error which has been collected, if there are any.
+    fn finish(self) -> Result<()> {
+        match self.0 {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The first of the things which cannot be used alongside an option, given
+/// with whether they are present, in the order they are checked.
+fn first_conflict<'a>(conflicts: &[(bool, &'a str)]) -> Option<&'a str> {
+    conflicts
+        .iter()
+        .find(|(present, _)| *present)
+        .map(|(_, conflict)| *conflict)
+}
+
+/// Reports that `subject`, such as "The `bench` option", cannot be used with
+/// the first of the conflicting things which is present. The error points at
+/// the key of the conflicting option if it is one, such as "the `module`
+/// option", and otherwise at the key of the subject.
+fn conflicts(options: &Options, subject: &str, conflicts: &[(bool, &str)]) -> Option<syn::Error> {
+    // The key of an option is the first word in its backticks.
+    let key = |text: &str| {
+        let key = text.split('`').nth(1)?.split(' ').next()?;
+        options.key(key)
+    };
+    let conflict = first_conflict(conflicts)?;
+    let span = key(conflict)
+        .or_else(|| key(subject))
+        .map_or_else(Span::call_site, Ident::span);
+    Some(syn::Error::new(
+        span,
+        format!("{} cannot be used with {}.", subject, conflict),
+    ))
+}
+
+/// Stores the value of an option, failing if it has already been set.
+fn set_option<T>(option: &mut Option<T>, key: &Ident, value: T) -> Result<()> {
+    if option.is_some() {
+        return Err(syn::Error::new(
+            key.span(),
+            format!("The `{}` option has already been set.", key),
+        ));
+    }
+
+    *option = Some(value);
+    Ok(())
+}
+
+/// Sets a flag option, failing if it has already been set.
+fn set_flag(flag: &mut bool, key: &Ident) -> Result<()> {
+    if *flag {
+        return Err(syn::Error::new(
+            key.span(),
+            format!("The `{}` option has already been set.", key),
+        ));
+    }
+
+    *flag = true;
+    Ok(())
+}
+
+#[derive(Clone)]
+struct Row {
+    attrs: Vec<Attribute>,
+    /// The position of the row in its table, or in each of the crossed tables.
+    index: Vec<usize>,
+    bracket: token::Bracket,
+    args: Punctuated<Expr, Token![,]>,
+    /// Where the row was read from, if it was not written in the table itself.
+    source: Option<String>,
+}
+
+impl Row {
+    /// Combines this row with a row from another table, as part of a `cross`.
+    fn cross(&self, other: &Row) -> Row {
+        let mut row = self.clone();
+        row.attrs.extend(other.attrs.iter().cloned());
+        row.index.extend(other.index.iter().copied());
+        row.args.extend(other.args.iter().cloned());
+        row
+    }
+
+    /// Expands a row with cells in `range!` columns into a row for each value
+    /// of the ranges. The ranges in a row are zipped together, so they must
+    /// have the same length.
+    fn expand_ranges(self, column_names: &Punctuated<Column, Token![,]>) -> Result<Vec<Row>> {
+        let mut ranges = vec![];
+        for (idx, column) in column_names.iter().enumerate() {
+            if let (
+                Column::Parameter(Parameter {
+                    cells: Cells::Ranges,
+                    ..
+                }),
+                Some(cell),
+            ) = (column, self.args.iter().nth(idx))
+            {
+                ranges.push((idx, cell, range_values(cell)?));
+            }
+        }
+
+        let len = match ranges.first() {
+            Some((_, _, values)) => values.len(),
+            None => return Ok(vec![self]),
+        };
+        if let Some((_, cell, _)) = ranges.iter().find(|(_, _, values)| values.len() != len) {
+            return Err(syn::Error::new_spanned(
+                cell,
+                "This range has a different length to the other ranges in its row.",
+            ));
+        }
+
+        let rows = (0..len).map(|value| {
+            let mut row = self.clone();
+            for (idx, _, values) in ranges.iter() {
+                row.args[*idx] = values[value].clone();
+            }
+            row.index.push(value);
+            row
+        });
+        Ok(rows.collect())
+    }
+
+    /// Parses a bracketed row, or a braced row of `column = cell` pairs, along
+    /// with any attributes which precede it. Rows are numbered from 1 in
+    /// errors about their cells.
+    fn parse(
+        input: ParseStream,
+        column_names: &Punctuated<Column, Token![,]>,
+        number: usize,
+    ) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+
+        let args_input;
+        let (bracket, args) = if input.peek(token::Brace) {
+            let brace = syn::braced!(args_input in input);
+            let args = parse_named_cells(&args_input, brace.span, column_names, number)?;
+            (token::Bracket(brace.span), args)
+        } else {
+            let bracket = syn::bracketed!(args_input in input);
+            (bracket, parse_cells(&args_input, column_names, number)?)
+        };
+
+        Ok(Row {
+            attrs,
+            index: vec![],
+            bracket,
+            args,
+            source: None,
+        })
+    }
+}
+
+/// The values of a cell in a `range!` column, which must be a range with
+/// integer literal bounds.
+fn range_values(cell: &Expr) -> Result<Vec<Expr>> {
+    let range = match cell {
+        Expr::Range(range) => range,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                cell,
+                "Expected a range, such as `0..5`.",
+            ))
+        }
+    };
+    let (from, to) = match (&range.from, &range.to) {
+        (Some(from), Some(to)) => (range_bound(from)?, range_bound(to)?),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                cell,
+                "This range must have both a start and an end.",
+            ))
+        }
+    };
+    let end = match range.limits {
+        syn::RangeLimits::HalfOpen(_) => to.0,
+        syn::RangeLimits::Closed(_) => to.0 + 1,
+    };
+    if from.0 >= end {
+        return Err(syn::Error::new_spanned(cell, "This range is empty."));
+    }
+
+    // The values are spanned at a bound of the range so that errors in their
+    // types point at the cell. A bound of zero is avoided, as lints would
+    // otherwise treat the other values as zero-prefixed literals.
+    let span = if from.0 == 0 {
+        range.to.span()
+    } else {
+        range.from.span()
+    };
+    let suffix = if from.1.is_empty() { to.1 } else { from.1 };
+    let values = (from.0..end).map(|value| {
+        let lit = LitInt::new(&format!("{}{}", value.abs(), suffix), span);
+        if value < 0 {
+            syn::parse_quote!(-#lit)
+        } else {
+            syn::parse_quote!(#lit)
+        }
+    });
+    Ok(values.collect())
+}
+
+/// The value and suffix of an integer literal which bounds a range.
+fn range_bound(bound: &Expr) -> Result<(i128, String)> {
+    let (negative, lit) = match bound {
+        Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => (true, &**expr),
+        bound => (false, bound),
+    };
+    match lit {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => {
+            let value: i128 = lit.base10_parse()?;
+            let value = if negative { -value } else { value };
+            Ok((value, lit.suffix().to_string()))
+        }
+        _ => Err(syn::Error::new_spanned(
+            bound,
+            "The bounds of a range must be integer literals.",
+        )),
+    }
+}
+
+/// Parses a pattern, which may have alternatives separated by `|`.
+fn parse_pattern(input: ParseStream) -> Result<Pat> {
+    let leading_vert: Option<Token![|]> = input.parse()?;
+    let first: Pat = input.parse()?;
+    if leading_vert.is_none() && !input.peek(Token![|]) {
+        return Ok(first);
+    }
+
+    let mut cases = Punctuated::new();
+    cases.push_value(first);
+    while input.peek(Token![|]) {
+        cases.push_punct(input.parse()?);
+        cases.push_value(input.parse()?);
+    }
+
+    Ok(Pat::Or(PatOr {
+        attrs: vec![],
+        leading_vert,
+        cases,
+    }))
+}
+
+/// Parses the comma-separated cells of a row, according to the kind of the
+/// column which each cell belongs to.
+fn parse_cells(
+    input: ParseStream,
+    column_names: &Punctuated<Column, Token![,]>,
+    row: usize,
+) -> Result<Punctuated<Expr, Token![,]>> {
+    let mut args = Punctuated::new();
+
+    while !input.is_empty() {
+        let column = column_names.iter().nth(args.len());
+        if input.peek(Token![,]) {
+            let message = match column {
+                Some(column) => format!("The `{}` cell of this row is empty.", column.label()),
+                None => "This cell is empty.".to_string(),
+            };
+            return Err(input.error(message));
+        }
+
+        let description = format!("cell {} of row {}", args.len() + 1, row);
+        let cell = match column {
+            Some(Column::Parameter(Parameter {
+                cells: Cells::Tuples,
+                elements,
+                ..
+            })) => parse_tuple_cells(input, column, elements.len(), &description)?,
+            _ => parse_cell(input, column, &description)?,
+        };
+        args.push_value(cell);
+
+        if input.is_empty() {
+            break;
+        }
+        if !input.peek(Token![,]) {
+            return Err(input.error(format!(
+                "Could not parse {} as {}. Cells are separated by commas.",
+                description,
+                cell_kind(column)
+            )));
+        }
+        args.push_punct(input.parse()?);
+    }
+
+    // The cells missing from the end of the row are filled in only if every
+    // missing column has a default, so that other rows are reported as short.
+    let defaults: Option<Vec<_>> = column_names
+        .iter()
+        .skip(args.len())
+        .map(Column::default)
+        .collect();
+    for default in defaults.into_iter().flatten() {
+        args.push(default.clone());
+    }
+
+    Ok(args)
+}
+
+/// Parses the cells of a row written as `column = cell` pairs, and puts them in
+/// the same order as the columns.
+fn parse_named_cells(
+    input: ParseStream,
+    row: Span,
+    column_names: &Punctuated<Column, Token![,]>,
+    number: usize,
+) -> Result<Punctuated<Expr, Token![,]>> {
+    let mut cells: Vec<Option<Expr>> = column_names.iter().map(|_| None).collect();
+
+    while !input.is_empty() {
+        let key = if input.peek(Token![const]) {
+            let marker: Token![const] = input.parse()?;
+            quote!(#marker)
+        } else {
+            let name: Ident = input.parse()?;
+            quote!(#name)
+        };
+        let bang: Option<Token![!]> = input.parse()?;
+        let label = format!("{}{}", key, if bang.is_some() { "!" } else { "" });
+
+        let tuple = column_names.iter().find(|column| match column {
+            Column::Parameter(Parameter { elements, .. }) => {
+                elements.iter().any(|element| *element == label)
+            }
+            _ => false,
+        });
+        if let Some(tuple) = tuple {
+            return Err(syn::Error::new_spanned(
+                &key,
+                format!(
+                    "The cells of the `{}` column can only be given in a bracketed row.",
+                    tuple.label()
+                ),
+            ));
+        }
+        let position = column_names
+            .iter()
+            .position(|column| column.label() == label)
+            .ok_or_else(|| {
+                syn::Error::new_spanned(&key, format!("There is no column named `{}`.", label))
+            })?;
+        input.parse::<Token![=]>()?;
+        let column = column_names.iter().nth(position);
+        let description = format!("the `{}` cell of row {}", label, number);
+        let cell = parse_cell(input, column, &description)?;
+
+        if cells[position].replace(cell).is_some() {
+            return Err(syn::Error::new_spanned(
+                key,
+                format!("The `{}` column has already been given a cell.", label),
+            ));
+        }
+
+        if input.is_empty() {
+            break;
+        }
+        if !input.peek(Token![,]) {
+            return Err(input.error(format!(
+                "Could not parse {} as {}. Cells are separated by commas.",
+                description,
+                cell_kind(column)
+            )));
+        }
+        input.parse::<Token![,]>()?;
+    }
+
+    let mut args = Punctuated::new();
+    for (column, cell) in column_names.iter().zip(cells) {
+        match cell.or_else(|| column.default().cloned()) {
+            Some(cell) => args.push(cell),
+            None => {
+                return Err(syn::Error::new(
+                    row,
+                    format!("This row has no cell for the `{}` column.", column.label()),
+                ))
+            }
+        }
+    }
+    Ok(args)
+}
+
+/// Parses a single cell, according to the kind of column it is in.
+/// The `description` of the cell, such as "cell 2 of row 3", is used to
+/// point out which cell could not be parsed.
+fn parse_cell(input: ParseStream, column: Option<&Column>, description: &str) -> Result<Expr> {
+    let cell = match column {
+        Some(Column::Pattern(_)) => {
+            parse_pattern(input).map(|pattern| Expr::Verbatim(pattern.into_token_stream()))
+        }
+        Some(Column::TypeArgument(_)) => input
+            .parse::<Type>()
+            .map(|ty| Expr::Verbatim(ty.into_token_stream())),
+        _ => input.parse(),
+    };
+    cell.map_err(|error| {
+        syn::Error::new(
+            error.span(),
+            format!(
+                "Could not parse {} as {}: {}",
+                description,
+                cell_kind(column),
+                error
+            ),
+        )
+    })
+}
+
+/// Parses the cells of a `tuple!` column, and groups them into a tuple.
+fn parse_tuple_cells(
+    input: ParseStream,
+    column: Option<&Column>,
+    len: usize,
+    description: &str,
+) -> Result<Expr> {
+    let mut cells = Punctuated::<Expr, Token![,]>::new();
+    loop {
+        cells.push_value(parse_cell(input, column, description)?);
+        if cells.len() == len {
+            break;
+        }
+        if input.is_empty() {
+            let label = column.map(Column::label).unwrap_or_default();
+            return Err(syn::Error::new(
+                input.span(),
+                format!(
+                    "The `{}` column needs {} cells, but this row only has {}.",
+                    label,
+                    len,
+                    cells.len()
+                ),
+            ));
+        }
+        cells.push_punct(input.parse()?);
+    }
+    Ok(syn::parse_quote!((#cells)))
+}
+
+/// What the cells of a column are parsed as, for errors about them.
+fn cell_kind(column: Option<&Column>) -> &'static str {
+    match column {
+        Some(Column::Pattern(_)) => "a pattern",
+        Some(Column::TypeArgument(_)) => "a type",
+        _ => "an expression",
+    }
+}
+
+enum Column {
+    /// A column whose cells are passed to the test function.
+    Parameter(Parameter),
+    /// The `name!` column, whose cells are used to name the generated cases.
+    CaseName(Ident),
+    /// A `ty!` column, whose cells are passed to the test function as type
+    /// arguments.
+    TypeArgument(Ident),
+    /// A `const!` column, whose cells are passed to the test function as const
+    /// generic arguments.
+    ConstArgument(Token![const]),
+    /// The column of patterns checked by `assert = matches`.
+    Pattern(Ident),
+    /// The column of values checked by `assert = eq`.
+    Expected(Ident),
+    /// The column of error messages checked by `assert = err`.
+    ErrorMessage(Ident),
+    /// The column named by the `panic_flag` option, whose `true` or `false`
+    /// cells decide whether each case should panic.
+    PanicFlag(Ident),
+    /// The `check!` column, whose cells are closures which are called with the
+    /// value returned by the test function.
+    Check(Ident),
+}
+
+impl Parse for Column {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![const]) && input.peek2(Token![!]) {
+            let marker = input.parse()?;
+            input.parse::<Token![!]>()?;
+            return Ok(Column::ConstArgument(marker));
+        }
+
+        let name = match input.parse()? {
+            ColumnName::Ident(name) => name,
+            ColumnName::Str(name) if input.peek(Token![!]) => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "Column markers must be identifiers, such as `name!`.",
+                ))
+            }
+            ColumnName::Str(name) => name.parse().map_err(|_| {
+                syn::Error::new(
+                    name.span(),
+                    "A quoted column name must be an identifier, such as `\"input\"`.",
+                )
+            })?,
+        };
+
+        if input.peek(Token![::]) {
+            return Err(input.error(
+                "Column names must be identifiers. The type of a column can be given after a `:`, such as `direction: Direction`.",
+            ));
+        }
+
+        if input.peek(Token![!]) {
+            input.parse::<Token![!]>()?;
+            return match name.to_string().as_str() {
+                "name" => Ok(Column::CaseName(name)),
+                "ty" => Ok(Column::TypeArgument(name)),
+                "check" => Ok(Column::Check(name)),
+                "range" | "strategy" | "platform" => {
+                    let cells = if name == "range" {
+                        Cells::Ranges
+                    } else if name == "platform" {
+                        Cells::Platforms
+                    } else if cfg!(feature = "proptest") {
+                        Cells::Strategies
+                    } else {
+                        return Err(syn::Error::new(
+                            name.span(),
+                            "`strategy!` columns need the `proptest` feature of `test_with_parameters`.",
+                        ));
+                    };
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let name = content.parse()?;
+                    Ok(Column::Parameter(Parameter {
+                        name,
+                        ty: parse_column_type(&content)?,
+                        default: None,
+                        transform: None,
+                        cells,
+                        elements: vec![],
+                    }))
+                }
+                "tuple" | "spread" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let elements = content.parse_terminated::<Ident, Token![,]>(Ident::parse)?;
+                    if elements.len() < 2 {
+                        let message = if name == "tuple" {
+                            "A `tuple!` column needs at least two cells, such as `tuple!(left, right)`."
+                        } else {
+                            "A `spread!` column needs at least two parameters, such as `spread!(left, right)`."
+                        };
+                        return Err(syn::Error::new(name.span(), message));
+                    }
+                    let names: Vec<_> = elements.iter().map(Ident::to_string).collect();
+                    Ok(Column::Parameter(Parameter {
+                        name: Ident::new(&names.join("_"), name.span()),
+                        ty: None,
+                        default: None,
+                        transform: None,
+                        cells: if name == "tuple" {
+                            Cells::Tuples
+                        } else {
+                            Cells::Spreads
+                        },
+                        elements: elements.into_iter().collect(),
+                    }))
+                }
+                "by_ref" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    Ok(Column::Parameter(Parameter {
+                        name: content.parse()?,
+                        ty: None,
+                        default: None,
+                        transform: None,
+                        cells: Cells::Statics,
+                        elements: vec![],
+                    }))
+                }
+                "into" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    Ok(Column::Parameter(Parameter {
+                        name: content.parse()?,
+                        ty: None,
+                        default: None,
+                        transform: Some(Box::new(syn::parse_quote_spanned! {
+                            name.span() => ::core::convert::Into::into
+                        })),
+                        cells: Cells::Values,
+                        elements: vec![],
+                    }))
+                }
+                _ => Err(syn::Error::new(name.span(), "Unknown column marker.")),
+            };
+        }
+
+        // A column marked with `?` defaults to `Default::default()`.
+        let optional: Option<Token![?]> = input.parse()?;
+        let ty = parse_column_type(input)?;
+        let default = match (optional, parse_column_default(input)?) {
+            (Some(_), Some(default)) => {
+                return Err(syn::Error::new_spanned(
+                    default,
+                    "A column marked with `?` already defaults to `Default::default()`, so it cannot be given another default cell.",
+                ))
+            }
+            (Some(mark), None) => Some(Box::new(syn::parse_quote_spanned! {
+                mark.span => ::core::default::Default::default()
+            })),
+            (None, default) => default,
+        };
+        let transform = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Column::Parameter(Parameter {
+            name,
+            ty,
+            default,
+            transform,
+            cells: Cells::Values,
+            elements: vec![],
+        }))
+    }
+}
+
+/// The name in a column header, which may be written as an identifier or as a
+/// string literal, such as `input` or `"input"`.
+enum ColumnName {
+    Ident(Ident),
+    Str(LitStr),
+}
+
+impl Parse for ColumnName {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(LitStr) {
+            Ok(ColumnName::Str(input.parse()?))
+        } else {
+            Ok(ColumnName::Ident(input.parse()?))
+        }
+    }
+}
+
+/// Parses the type of a column, if it has one.
+fn parse_column_type(input: ParseStream) -> Result<Option<Box<Type>>> {
+    if input.peek(Token![:]) && !input.peek(Token![::]) {
+        input.parse::<Token![:]>()?;
+        Ok(Some(input.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parses the default cell of a column, if it has one.
+fn parse_column_default(input: ParseStream) -> Result<Option<Box<Expr>>> {
+    if input.peek(Token![=]) && !input.peek(Token![=>]) {
+        input.parse::<Token![=]>()?;
+        Ok(Some(input.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// A column which is passed to the test function.
+struct Parameter {
+    name: Ident,
+    /// The type of the column, if it was given in the header.
+    ty: Option<Box<Type>>,
+    /// The cell used by rows which leave this column out.
+    default: Option<Box<Expr>>,
+    /// A function which each cell is passed through before the test function.
+    transform: Option<Box<Expr>>,
+    /// How the cells of the column are passed to the test function.
+    cells: Cells,
+    /// The names of the cells grouped by a `tuple!` column, or of the
+    /// parameters covered by a `spread!` column.
+    elements: Vec<Ident>,
+}
+
+impl Parameter {
+    /// The number of the test function's parameters which the column passes.
+    fn width(&self) -> usize {
+        match self.cells {
+            Cells::Spreads => self.elements.len(),
+            _ => 1,
+        }
+    }
+}
+
+/// The kinds of cells which a parameter column can have.
+#[derive(Clone, Copy, PartialEq)]
+enum Cells {
+    /// Cells which are passed to the test function as they are.
+    Values,
+    /// The cells of a `range!` column, which are ranges that expand into a
+    /// case for each of their values.
+    Ranges,
+    /// The cells of a `strategy!` column, which are `proptest` strategies that
+    /// generate the values passed to the test function.
+    Strategies,
+    /// The cells of a `tuple!` column, which are passed to the test function
+    /// together as a tuple.
+    Tuples,
+    /// The cells of a `spread!` column, which are tuples whose elements are
+    /// passed to the test function as separate parameters.
+    Spreads,
+    /// The cells of a `by_ref!` column, which are each placed in a `static`
+    /// that is passed to the test function by reference.
+    Statics,
+    /// The cells of a `platform!` column, which may give one value for
+    /// Windows and another for every other platform.
+    Platforms,
+}
+
+impl Column {
+    /// How the column is written in the header of the table.
+    fn label(&self) -> String {
+        match self {
+            Column::Parameter(Parameter {
+                cells: cells @ (Cells::Tuples | Cells::Spreads),
+                elements,
+                ..
+            }) => {
+                let marker = if *cells == Cells::Tuples {
+                    "tuple"
+                } else {
+                    "spread"
+                };
+                let elements: Vec<_> = elements.iter().map(Ident::to_string).collect();
+                format!("{}!({})", marker, elements.join(", "))
+            }
+            Column::Parameter(Parameter { name, .. })
+            | Column::Pattern(name)
+            | Column::Expected(name)
+            | Column::ErrorMessage(name)
+            | Column::PanicFlag(name) => name.to_string(),
+            Column::CaseName(name) | Column::TypeArgument(name) | Column::Check(name) => {
+                format!("{}!", name)
+            }
+            Column::ConstArgument(_) => "const!".to_string(),
+        }
+    }
+
+    /// The cell used by rows which leave this column out, if it has one.
+    fn default(&self) -> Option<&Expr> {
+        match self {
+            Column::Parameter(Parameter { default, .. }) => default.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Passes a cell through the column's transform, if it has one.
+    fn transformed(&self, cell: Expr) -> Expr {
+        let transform = match self {
+            Column::Parameter(Parameter {
+                transform: Some(transform),
+                ..
+            }) => transform,
+            _ => return cell,
+        };
+        match &**transform {
+            Expr::Path(_) => syn::parse_quote!(#transform(#cell)),
+            _ => syn::parse_quote!((#transform)(#cell)),
+        }
+    }
+}
+
+impl ToTokens for Column {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            Column::Parameter(Parameter {
+                name,
+                ty,
+                default,
+                transform,
+                cells,
+                elements,
+            }) => {
+                let ty = ty.as_ref().map(|ty| quote! { : #ty });
+                let default = default.as_ref().map(|default| quote! { = #default });
+                let transform = transform.as_ref().map(|transform| quote! { => #transform });
+                tokens.extend(match cells {
+                    Cells::Values => quote! { #name #ty #default #transform },
+                    Cells::Ranges => quote_spanned! { name.span() => range!(#name #ty) },
+                    Cells::Strategies => quote_spanned! { name.span() => strategy!(#name #ty) },
+                    Cells::Tuples => quote_spanned! { name.span() => tuple!(#(#elements),*) },
+                    Cells::Spreads => quote_spanned! { name.span() => spread!(#(#elements),*) },
+                    Cells::Statics => quote_spanned! { name.span() => by_ref!(#name) },
+                    Cells::Platforms => quote_spanned! { name.span() => platform!(#name #ty) },
+                });
+            }
+            Column::CaseName(name) | Column::TypeArgument(name) | Column::Check(name) => {
+                tokens.extend(quote_spanned! { name.span() => #name! })
+            }
+            Column::ConstArgument(marker) => {
+                tokens.extend(quote_spanned! { marker.span => #marker! })
+            }
+            Column::Pattern(name)
+            | Column::Expected(name)
+            | Column::ErrorMessage(name)
+            | Column::PanicFlag(name) => name.to_tokens(tokens),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains_await, generate, TableSyntax};
+    use quote::{quote, ToTokens};
+
+    fn table_error(table: &str) -> String {
+        match syn::parse_str::<TableSyntax>(table) {
+            Ok(_) => panic!("The table `{}` was parsed.", table),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    fn errors(attr: proc_macro2::TokenStream, item: proc_macro2::TokenStream) -> Vec<String> {
+        match generate(attr, item) {
+            Ok(_) => panic!("The cases were generated."),
+            Err(error) => error.into_iter().map(|error| error.to_string()).collect(),
+        }
+    }
+
+    /// Each error for a test function, with the source code it points to.
+    fn diagnostics(attr: &str, item: &str) -> Vec<(String, String)> {
+        let error = match generate(attr.parse().unwrap(), item.parse().unwrap()) {
+            Ok(_) => panic!("The cases were generated."),
+            Err(error) => error,
+        };
+        error
+            .into_iter()
+            .map(|error| {
+                let span = error.span();
+                let (start, end) = (span.start(), span.end());
+                let text = match (start.line, end.line) {
+                    (1, 1) => attr.get(start.column..end.column).unwrap_or_default(),
+                    _ => "",
+                };
+                (error.to_string(), text.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arity_mismatches() {
+        assert_eq!(
+            diagnostics("[a, b] [1, 2]", "fn f(a: u8) {}"),
+            [(
+                "Number of parameters does not match the test function's arity.".to_string(),
+                "a, b".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn short_rows() {
+        assert_eq!(
+            diagnostics("[a, b] [1, 2] [3]", "fn f(a: u8, b: u8) {}"),
+            [(
+                "Expected 2 arguments (a, b) but found 1.".to_string(),
+                "[3]".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn long_rows() {
+        assert_eq!(
+            diagnostics("[a, b] [1, 2, 3 + 4, 5]", "fn f(a: u8, b: u8) {}"),
+            [(
+                "Expected 2 arguments (a, b) but found 4.".to_string(),
+                "3 + 4".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn duplicate_column_names() {
+        assert_eq!(
+            diagnostics("[input, input] [1, 2]", "fn f(a: u8, b: u8) {}"),
+            [(
+                "This column name is used by another column.".to_string(),
+                "input".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn non_literal_panic_flags() {
+        assert_eq!(
+            diagnostics("panic_flag = fails, [a, fails] [1, !true]", "fn f(a: u8) {}"),
+            [(
+                "The cells of the `panic_flag` column must be `true` or `false`, as they decide whether the case is marked `#[should_panic]`.".to_string(),
+                "!true".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_affixes() {
+        assert_eq!(
+            diagnostics(r#"prefix = "fast-", [a] [1]"#, "fn f(a: u8) {}"),
+            [(
+                "The `prefix` option must be made of characters which can be used in an identifier, such as `fast_`.".to_string(),
+                r#""fast-""#.to_string(),
+            )]
+        );
+        assert_eq!(
+            errors(
+                quote!(suffix = "", [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn misplaced_no_panic_assertions() {
+        assert_eq!(
+            errors(
+                quote!(assert = no_panic, expected = 1, [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["`assert = no_panic` cannot be used with the `expected` option, as there is no column to check."]
+        );
+        assert_eq!(
+            errors(
+                quote!(assert = no_panic, [a][1]),
+                quote!(
+                    async fn f(a: u8) {}
+                )
+            ),
+            ["`assert = no_panic` cannot be used with an async test function, as a panic cannot be caught across an `.await`."]
+        );
+    }
+
+    #[test]
+    fn misplaced_by_ref_columns() {
+        assert_eq!(
+            errors(
+                quote!([by_ref!(a)][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["A `by_ref!` column must be passed to a parameter whose type is a shared reference to a type which is not generic, such as `&Config`."]
+        );
+        assert_eq!(
+            errors(
+                quote!(single_test, [by_ref!(a)][1]),
+                quote!(
+                    fn f(a: &u8) {}
+                )
+            ),
+            ["`by_ref!` columns cannot be used with the `single_test` option."]
+        );
+    }
+
+    #[test]
+    fn misplaced_const_checks() {
+        assert_eq!(
+            errors(
+                quote!(const_check, [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `const_check` option needs the test function to be a `const fn`, as it is called when the crate is compiled."]
+        );
+        assert_eq!(
+            errors(
+                quote!(const_check, [a][1]),
+                quote!(
+                    const fn f(a: u8) -> u8 {
+                        a
+                    }
+                )
+            ),
+            ["The `const_check` option needs `assert = eq` when the test function returns a value, so that the value is checked."]
+        );
+        assert_eq!(
+            errors(
+                quote!(const_check, assert = matches, [a, expected][1, 1]),
+                quote!(
+                    const fn f(a: u8) -> u8 {
+                        a
+                    }
+                )
+            ),
+            ["The `const_check` option cannot be used with an assertion other than `assert = eq`."]
+        );
+        assert_eq!(
+            errors(
+                quote!(const_check, [check!][|_| ()]),
+                quote!(
+                    const fn f() {}
+                )
+            ),
+            ["This column cannot be used with the `const_check` option."]
+        );
+    }
+
+    #[test]
+    fn malformed_name_templates() {
+        assert_eq!(
+            errors(
+                quote!(name_template = "{fn}_{row}", [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["Unknown placeholder `{row}`. Expected `{fn}`, `{index}` or `{name_column}`."]
+        );
+        assert_eq!(
+            errors(
+                quote!(name_template = "{fn}_case", [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `name_template` option must use `{index}` or `{name_column}`, so that each case has its own name."]
+        );
+        assert_eq!(
+            errors(
+                quote!(name_template = "{index}_{fn}", [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `name_template` option gives a case the name `0_f`, which is not an identifier."]
+        );
+        assert_eq!(
+            errors(
+                quote!(name_template = "{fn}_{name_column}", [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `{name_column}` placeholder needs a `name!` column."]
+        );
+        assert_eq!(
+            errors(
+                quote!(name_template = "{fn}_{index}", prefix = "fast_", [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `name_template` option cannot be used with the `prefix` option."]
+        );
+    }
+
+    #[test]
+    fn misplaced_references() {
+        assert_eq!(
+            errors(
+                quote!(reference = g, assert = eq, [a, b][1, 2]),
+                quote!(
+                    fn f(a: u8) -> u8 {
+                        a
+                    }
+                )
+            ),
+            ["The `reference` option cannot be used with the `assert` option."]
+        );
+        assert_eq!(
+            errors(
+                quote!(reference = g, [spread!(a, b)][(1, 2)]),
+                quote!(
+                    fn f(a: u8, b: u8) -> u8 {
+                        a + b
+                    }
+                )
+            ),
+            ["This column cannot be used with the `reference` option."]
+        );
+    }
+
+    #[test]
+    fn large_tables_are_warned_about() {
+        let generated = |attr| {
+            generate(
+                attr,
+                quote!(
+                    fn f(a: u8) {}
+                ),
+            )
+            .unwrap()
+            .to_string()
+        };
+        assert!(generated(quote!(max_cases = 2, [a][1][2][3])).contains("deprecated"));
+        assert!(!generated(quote!(max_cases = 3, [a][1][2][3])).contains("deprecated"));
+        assert!(!generated(quote!(max_cases = 2, single_test, [a][1][2][3])).contains("deprecated"));
+    }
+
+    #[test]
+    fn malformed_platform_cells() {
+        assert_eq!(
+            table_error(r#"[platform!(a)] ["a" | "b" | "c"]"#),
+            r#"A `platform!` cell can only have two values, one for Windows after the `|`, such as `"\n" | "\r\n"`."#
+        );
+        assert_eq!(
+            table_error("assert = matches, [a, platform!(b)] [1, Some(_) | None]"),
+            "A `platform!` column cannot hold patterns, as `|` separates their alternatives."
+        );
+    }
+
+    #[test]
+    fn malformed_block_rows() {
+        assert_eq!(
+            table_error("rows { a = 1 b = 2; a = 3 }"),
+            "This row has no cell for the `b` column."
+        );
+        assert_eq!(
+            table_error("rows { a = 1; a = 2 c = 3 }"),
+            "There is no column named `c`."
+        );
+        assert_eq!(
+            table_error("rows { (1, 2) b = 2 }"),
+            "Expected a `column = cell` pair, such as `input = 1`."
+        );
+        assert_eq!(table_error("rows { ; }"), "This block has no rows.");
+    }
+
+    #[test]
+    fn tables_are_documented() {
+        let generated = generate(
+            quote!(doc_table, [name!, a, b][one, 1, |x: u8| x][two, 2, (1, 2)]),
+            quote!(
+                fn f<T>(a: u8, b: T) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        for line in [
+            "| name! | a | b |",
+            "| --- | --- | --- |",
+            "| `one` | `1` | `\\| x : u8 \\| x` |",
+            "| `two` | `2` | `(1 , 2)` |",
+        ] {
+            assert!(generated.contains(&format!("{:?}", line)), "{}", line);
+        }
+        assert_eq!(
+            errors(
+                quote!(doc_table, inline, [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `doc_table` option cannot be used with the `inline` option, which leaves out the test function that it documents."]
+        );
+    }
+
+    #[test]
+    fn separate_cases_alongside_single_tests() {
+        assert_eq!(
+            errors(
+                quote!(single_test = both, [name!, a][all, 1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["This case has the same name as the test which runs every case, `f_all`."]
+        );
+        assert_eq!(
+            errors(
+                quote!(single_test = all, [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["Unknown `single_test` mode. Expected `both`."]
+        );
+        let generated = generate(
+            quote!(single_test = both, [a][1]),
+            quote!(
+                #[ignore]
+                fn f(a: u8) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        assert_eq!(generated.matches("# [ignore]").count(), 2);
+    }
+
+    #[test]
+    fn slow_rows_are_ignored_under_miri() {
+        let generated = |attr| {
+            generate(
+                attr,
+                quote!(
+                    fn f(a: u8) {}
+                ),
+            )
+            .unwrap()
+            .to_string()
+        };
+        let ignored = quote!(#[cfg_attr(miri, ignore)]).to_string();
+        let table = quote!([a][1] #[tag(slow)] [2] #[tag(large, slow)] [3] #[tag(large)] [4]);
+        assert_eq!(
+            generated(quote!(miri = skip_slow, #table))
+                .matches(&ignored)
+                .count(),
+            2
+        );
+        assert_eq!(generated(table).matches(&ignored).count(), 0);
+    }
+
+    #[test]
+    fn async_runtimes() {
+        let generated = |attr| {
+            generate(
+                attr,
+                quote!(
+                    async fn f(a: u8) {}
+                ),
+            )
+            .unwrap()
+            .to_string()
+        };
+        let default = quote!(#[tokio::test]).to_string();
+        assert_eq!(generated(quote!([a][1][2])).matches(&default).count(), 2);
+        let flavored =
+            quote!(#[tokio::test(flavor = "multi_thread", worker_threads = 4)]).to_string();
+        assert_eq!(
+            generated(quote!(
+                runtime = tokio::test(flavor = "multi_thread", worker_threads = 4),
+                [a][1][2]
+            ))
+            .matches(&flavored)
+            .count(),
+            2
+        );
+        assert_eq!(
+            generated(quote!(
+                single_test,
+                runtime = tokio::test(flavor = "multi_thread"),
+                [a][1][2]
+            ))
+            .matches(&quote!(#[tokio::test(flavor = "multi_thread")]).to_string())
+            .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn homogeneous_columns_are_checked_with_their_types() {
+        let table = quote!(
+            [ a   , b    , items      , c: u32 => u32::from ]
+            [ 1   , None , Vec::new() , 1u8                 ]
+            [ "x" , None , vec![1]    , 2u16                ]
+        );
+        let generated = generate(
+            quote!(homogeneous, #table),
+            quote!(
+                fn f<'a>(a: u8, b: Option<&'a str>, items: Vec<u8>, c: u32) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        let checks = generated.matches("const _ : fn () = ||").count();
+        assert_eq!(checks, 2);
+        // The check which rejects the second row, as its cell is not a `u8`.
+        assert!(generated.contains(&quote!(let _: [u8; 2usize] = [1, "x"];).to_string()));
+        assert!(generated
+            .contains(&quote!(let _: [Vec<u8>; 2usize] = [Vec::new(), vec![1]];).to_string()));
+    }
+
+    #[test]
+    fn reference_cells_which_use_earlier_columns() {
+        assert_eq!(
+            diagnostics(
+                "reference = g, [v, n] [vec![1, 2], v.len()]",
+                "fn f(v: Vec<u8>, n: usize) -> usize { n }"
+            ),
+            [(
+                "This cell uses the `v` column, which may have been moved into the test function, so it cannot be used with the `reference` option.".to_string(),
+                "v".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "test_case")]
+    fn test_case_attributes() {
+        let generated = generate(
+            quote!(test_case, doc_table, prefix = "small_", [name!, a][one, 1][two, 1 + 1]),
+            quote!(
+                fn f(a: u8) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        let attrs = quote! {
+            #[::test_case::test_case(1 ; "small_one")]
+            #[::test_case::test_case(1 + 1 ; "small_two")]
+        };
+        assert!(generated.starts_with(&attrs.to_string()));
+        assert!(generated.contains("| name! | a |"));
+        assert!(generated.ends_with(
+            &quote!(
+                fn f(a: u8) {}
+            )
+            .to_string()
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "test_case")]
+    fn options_which_test_case_cannot_honour() {
+        for option in [
+            quote!(teardown = cleanup()),
+            quote!(homogeneous),
+            quote!(no_std),
+            quote!(attr = tokio::test),
+            quote!(expose_count),
+        ] {
+            let errors = errors(
+                quote!(test_case, #option, [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                ),
+            );
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0].starts_with("The `test_case` option cannot be used with"));
+        }
+    }
+
+    #[test]
+    fn awaits_are_found_in_groups() {
+        assert!(contains_await(quote!(cleanup().await)));
+        assert!(contains_await(quote!({
+            db.close().await;
+        })));
+        assert!(contains_await(quote!(drop((stop().await, 1)))));
+        assert!(!contains_await(quote!(cleanup())));
+        assert!(!contains_await(quote!(wait(await_all))));
+    }
+
+    #[test]
+    fn async_teardowns_are_awaited_after_the_call() {
+        let generated = generate(
+            quote!(setup = open().await, teardown = close().await, [a][1]),
+            quote!(
+                async fn f(db: Db, a: u8) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        let position = |tokens: proc_macro2::TokenStream| {
+            generated
+                .find(&tokens.to_string())
+                .unwrap_or_else(|| panic!("`{}` was not generated.", tokens))
+        };
+        let setup = position(quote!(open().await));
+        let call = position(quote!(f(fixture, 1).await));
+        let teardown = position(quote!(let _ = close().await;));
+        assert!(setup < call && call < teardown);
+    }
+
+    #[test]
+    fn renamed_helpers_are_private() {
+        let generated = |helper| {
+            generate(
+                quote!(helper = #helper, [a][1]),
+                quote!(
+                    pub fn f(a: u8) {}
+                ),
+            )
+            .unwrap()
+            .to_string()
+        };
+        assert!(generated(quote!(f_impl)).starts_with(
+            &quote!(
+                #[doc(hidden)]
+                fn f_impl(a: u8) {}
+            )
+            .to_string()
+        ));
+        assert!(generated(quote!(pub(crate) f_impl)).starts_with(
+            &quote!(
+                #[doc(hidden)]
+                pub(crate) fn f_impl(a: u8) {}
+            )
+            .to_string()
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "proptest")]
+    fn strategies_are_run_with_proptest() {
+        let generated = generate(
+            quote!([strategy!(input: u32), divisor][0..100u32, 1]),
+            quote!(
+                fn f(input: u32, divisor: u32) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        let body = quote! {
+            ::proptest::proptest!(|(input in 0..100u32)| {
+                let input: u32 = input;
+                f(input, 1)
+            });
+        };
+        assert!(generated.contains(&body.to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "tempfile")]
+    fn temporary_directories_are_passed_to_each_case() {
+        let generated = generate(
+            quote!(tempdir, [name][a]),
+            quote!(
+                fn f(dir: &std::path::Path, name: &str) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        let body = quote! {
+            let fixture = ::tempfile::TempDir::new()
+                .expect("The temporary directory for this case could not be created.");
+            f(fixture.path(), a)
+        };
+        assert!(generated.contains(&body.to_string()));
+        assert_eq!(
+            errors(
+                quote!(tempdir, setup = 1, [name][a]),
+                quote!(
+                    fn f(dir: &std::path::Path, name: &str) {}
+                )
+            ),
+            ["The `tempdir` option cannot be used with the `setup` option."]
+        );
+    }
+
+    #[test]
+    fn from_arrays_are_checked_against_their_count() {
+        let generated = generate(
+            quote!(from = CASES, count = 2),
+            quote!(
+                fn f(a: u8) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        let check = quote! {
+            const _: () = ::core::assert!(
+                CASES.len() == 2usize,
+                "`CASES` must have 2 elements, as given by the `count` option."
+            );
+        };
+        assert!(generated.contains(&check.to_string()));
+    }
+
+    #[test]
+    fn misplaced_options_of_stacked_tables() {
+        let passed_on = super::passed_on(quote!(offset = 2));
+        assert_eq!(
+            errors(
+                quote!(#passed_on, start = 1, [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `start` option of this table must be at least 2, as the cases of the tables above it are numbered up to 1."]
+        );
+        assert_eq!(
+            errors(
+                quote!(expose_names, [a][1]),
+                quote!(
+                    #[test_with_parameters([a][2])]
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `expose_names` option must be given to the last `#[test_with_parameters]` attribute on the test function, which declares it for the cases of every attribute."]
+        );
+    }
+
+    #[test]
+    fn options_of_stacked_tables_cannot_be_written() {
+        for option in [
+            quote!(offset = 2),
+            quote!(__offset = 2),
+            quote!(cases = ["f_1"]),
+            quote!(__serial),
+        ] {
+            assert_eq!(
+                errors(
+                    quote!(#option, [a][1]),
+                    quote!(
+                        fn f(a: u8) {}
+                    )
+                ),
+                ["Unknown option."]
+            );
+        }
+    }
+
+    #[test]
+    fn fixtures_are_lent_to_references_which_are_not_static() {
+        let expanded = |input: &str| {
+            let attr = quote!(setup = make(), [x][1]);
+            let item: proc_macro2::TokenStream = input.parse().unwrap();
+            generate(attr, item).unwrap().to_string()
+        };
+        let call = |arg| quote!(f(#arg, 1)).to_string();
+        let lent = expanded("fn f(fixture: &Fixture, x: u8) {}");
+        assert!(lent.contains(&call(quote!(&fixture))));
+        let moved = expanded("fn f(fixture: &'static str, x: u8) {}");
+        assert!(moved.contains(&call(quote!(fixture))));
+    }
+
+    #[test]
+    fn tagged_names_which_are_used_by_other_cases() {
+        assert_eq!(
+            diagnostics(
+                "[name!, x] [slow_long, 1] #[tag(slow)] [long, 2]",
+                "fn f(x: u8) {}"
+            ),
+            [(
+                "This case name is used by another case.".to_string(),
+                "long".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn setups_are_evaluated_before_the_call() {
+        let combinations = [
+            ("", quote!(open())),
+            ("", quote!(open()?)),
+            ("async", quote!(connect().await)),
+            ("async", quote!(connect().await?)),
+        ];
+        for (asyncness, setup) in combinations {
+            let attr = quote!(setup = #setup, [x] [1]);
+            let item: proc_macro2::TokenStream = format!(
+                "{} fn f(db: Db, x: u8) -> Result<(), E> {{ Ok(()) }}",
+                asyncness
+            )
+            .parse()
+            .unwrap();
+            let awaited = (!asyncness.is_empty()).then(|| quote!(.await));
+            // The call ends the case, so a setup which fails returns its error
+            // before the test function is run.
+            let body = quote! {
+                let fixture = #setup;
+                f(fixture, 1) #awaited
+            };
+            let expanded = generate(attr, item).unwrap().to_string();
+            assert!(expanded.contains(&format!("{} }}", body)), "{}", expanded);
+        }
+    }
+
+    #[test]
+    fn failing_cases_report_their_rows() {
+        let expanded = generate(
+            quote!([x][1]),
+            quote!(
+                fn f(x: u8) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        let report = quote! {
+            ::std::eprintln!(
+                "Case `f_case0` failed. Its row is at {}.",
+                ::std::concat!(::std::file!(), ":", ::std::line!())
+            );
+        };
+        assert!(expanded.contains(&report.to_string()), "{}", expanded);
+    }
+
+    #[test]
+    fn notes_are_added_to_failure_messages() {
+        let attr = quote! {
+            [x]
+            #[note = "regression for {issue} #123"]
+            #[note = "second note"]
+            [1]
+        };
+        let expanded = generate(
+            attr,
+            quote!(
+                fn f(x: u8) {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        let message = "Case `f_case0` failed. Its row is at {}.\nNote: regression for {{issue}} #123\nNote: second note";
+        let report = format!("eprintln ! ({} ,", quote!(#message));
+        assert!(expanded.contains(&report), "{}", expanded);
+    }
+
+    #[test]
+    fn sorted_cases_are_generated_in_the_order_of_their_names() {
+        let order = |attr: proc_macro2::TokenStream| {
+            let expanded = generate(
+                attr,
+                quote!(
+                    fn f(x: u8) {}
+                ),
+            )
+            .unwrap()
+            .to_string();
+            let position = |name| expanded.find(&format!("fn {} ()", name)).unwrap();
+            let mut names = ["f_b", "f_c", "f_a"];
+            names.sort_by_key(|name| position(*name));
+            names
+        };
+        let rows = quote!([name!, x] [b, 1] [c, 2] [a, 3]);
+        assert_eq!(order(rows.clone()), ["f_b", "f_c", "f_a"]);
+        assert_eq!(order(quote!(sorted, #rows)), ["f_a", "f_b", "f_c"]);
+    }
+
+    #[test]
+    fn items_which_are_not_functions() {
+        assert_eq!(
+            errors(
+                quote!([a][1]),
+                quote!(
+                    struct Cases;
+                )
+            ),
+            ["`#[test_with_parameters]` can only be applied to functions."]
+        );
+    }
+
+    #[test]
+    fn strict_names_which_do_not_match_their_parameters() {
+        assert_eq!(
+            diagnostics(
+                "strict, [input, expected] [1, 2]",
+                "fn f(input: u8, output: u8) {}"
+            ),
+            [(
+                "This column does not have the same name as the parameter, `output`.".to_string(),
+                "expected".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn commas_between_rows() {
+        assert_eq!(
+            table_error("[a] [1], [2]"),
+            "Rows are separated by placing them next to each other, not by commas. Remove this comma."
+        );
+    }
+
+    #[test]
+    fn column_names_which_are_paths() {
+        assert_eq!(
+            table_error("[direction::Direction] [Direction::Up]"),
+            "Column names must be identifiers. The type of a column can be given after a `:`, such as `direction: Direction`."
+        );
+    }
+
+    #[test]
+    fn unknown_naming() {
+        assert_eq!(
+            table_error("naming = random, [a] [1]"),
+            "Unknown naming. Expected `index` or `hash`."
+        );
+    }
+
+    #[test]
+    fn malformed_range_cells() {
+        assert_eq!(
+            diagnostics("[range!(a)] [5]", "fn f(a: u8) {}"),
+            [(
+                "Expected a range, such as `0..5`.".to_string(),
+                "5".to_string()
+            )]
+        );
+        assert_eq!(
+            diagnostics("[range!(a)] [1..]", "fn f(a: u8) {}"),
+            [(
+                "This range must have both a start and an end.".to_string(),
+                "1..".to_string()
+            )]
+        );
+        assert_eq!(
+            diagnostics("[range!(a)] [3..3]", "fn f(a: u8) {}"),
+            [("This range is empty.".to_string(), "3..3".to_string())]
+        );
+        assert_eq!(
+            diagnostics(
+                "[range!(a), range!(b)] [0..3, 1..=2]",
+                "fn f(a: u8, b: u8) {}"
+            ),
+            [(
+                "This range has a different length to the other ranges in its row.".to_string(),
+                "1..=2".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn malformed_braced_rows() {
+        assert_eq!(
+            table_error("[a, b] { a = 1, c = 2 }"),
+            "There is no column named `c`."
+        );
+        assert_eq!(
+            table_error("[a, b] { a = 1, a = 2 }"),
+            "The `a` column has already been given a cell."
+        );
+        assert_eq!(
+            table_error("[a, b] { b = 2 }"),
+            "This row has no cell for the `a` column."
+        );
+    }
+
+    #[test]
+    fn checked_columns_with_types() {
+        assert_eq!(
+            diagnostics("assert = eq, [a, b: u8] [1, 2]", "fn f(a: u8) -> u8 { a }"),
+            [(
+                "This column is checked against the test function's value, so it cannot be given a type.".to_string(),
+                "u8".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn cells_which_use_later_columns() {
+        assert_eq!(
+            diagnostics("[a, b] [b + 1, 2]", "fn f(a: u8, b: u8) {}"),
+            [(
+                "The `b` column comes after this cell, so its value cannot be used here."
+                    .to_string(),
+                "b".to_string(),
+            )]
+        );
+        assert!(generate(
+            quote!([a, b] [self::b() + 1, 2]),
+            quote!(
+                fn f(a: u8, b: u8) {}
+            )
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn problems_with_each_row_are_reported_together() {
+        assert_eq!(
+            errors(
+                quote!([x] #[timeout(1)] #[timeout(2)] [1] #[timeout(1)] #[timeout(2)] [2]),
+                quote!(
+                    fn f(x: u8) {}
+                )
+            ),
+            [
+                "A row may only have one `#[timeout]` attribute.",
+                "A row may only have one `#[timeout]` attribute.",
+            ]
+        );
+        assert_eq!(
+            errors(
+                quote!(no_std, [x] #[note = "a"] [1] #[should_panic] [2]),
+                quote!(
+                    fn f(x: u8) -> Result<(), ()> {
+                        Ok(())
+                    }
+                )
+            ),
+            [
+                "`#[note]` cannot be used with the `no_std` option.",
+                "`#[should_panic]` cannot be used on a test which returns a `Result`.",
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicts_point_at_their_options() {
+        assert_eq!(
+            diagnostics("show_cells, single_test, [a] [1]", "fn f(a: u8) {}"),
+            [(
+                "The `show_cells` option cannot be used with the `single_test` option.".to_string(),
+                "single_test".to_string(),
+            )]
+        );
+        assert_eq!(
+            diagnostics("no_helper, [a] [1]", "fn f(a: u8) {}"),
+            [(
+                "The `no_helper` option cannot be used with a test which has no `call` option to give the function which the cases call.".to_string(),
+                "no_helper".to_string(),
+            )]
+        );
+    }
+
+    /// Expands the attributes stacked on a test function one after another, as
+    /// the compiler would.
+    fn generate_stacked(
+        attr: proc_macro2::TokenStream,
+        item: proc_macro2::TokenStream,
+    ) -> syn::Result<proc_macro2::TokenStream> {
+        let file: syn::File = syn::parse2(generate(attr, item)?).unwrap();
+        let mut expanded = proc_macro2::TokenStream::new();
+        for item in file.items {
+            let stacked = match &item {
+                syn::Item::Fn(test_fn) => test_fn
+                    .attrs
+                    .iter()
+                    .position(|attr| attr.path.is_ident("test_with_parameters")),
+                _ => None,
+            };
+            match (item, stacked) {
+                (syn::Item::Fn(mut test_fn), Some(idx)) => {
+                    let attr = test_fn.attrs.remove(idx);
+                    let table = syn::parse2::<proc_macro2::Group>(attr.tokens).unwrap();
+                    expanded.extend(generate_stacked(table.stream(), test_fn.to_token_stream())?);
+                }
+                (item, _) => item.to_tokens(&mut expanded),
+            }
+        }
+        Ok(expanded)
+    }
+
+    #[test]
+    fn stacked_tables_which_declare_the_same_items() {
+        let stacked_errors = |upper, lower| {
+            let item = quote! {
+                #[test_with_parameters(#lower)]
+                fn f(a: u8) {}
+            };
+            match generate_stacked(upper, item) {
+                Ok(_) => vec![],
+                Err(error) => error.into_iter().map(|error| error.to_string()).collect(),
+            }
+        };
+        assert_eq!(
+            stacked_errors(quote!(module, [a][1]), quote!(module, [a][2])),
+            ["The `module` option can only be given to one of the `#[test_with_parameters]` attributes on the test function, as each would declare a module with the same name."]
+        );
+        assert_eq!(
+            stacked_errors(quote!(single_test, [a][1]), quote!(single_test, [a][2])),
+            ["The `single_test` option can only be given to one of the `#[test_with_parameters]` attributes on the test function, as each would declare a test named `f_all`."]
+        );
+        assert_eq!(
+            stacked_errors(quote!(module, [a][1]), quote!(single_test, [a][2])),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            stacked_errors(quote!([name!, a][one, 1]), quote!([name!, a][two, 2][one, 3])),
+            ["This case name is used by a case of another `#[test_with_parameters]` attribute on the test function."]
+        );
+        assert_eq!(
+            stacked_errors(
+                quote!([name!, a] #[cfg(unix)] [one, 1]),
+                quote!([name!, a] #[cfg(not(unix))] [one, 2])
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn case_names_which_are_not_identifiers() {
+        assert_eq!(
+            errors(
+                quote!([name!, a]["x²", 1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["This case name contains a character which cannot be used in an identifier."]
+        );
+        assert!(generate(
+            quote!([name!, a]["Größe", 1]),
+            quote!(
+                fn f(a: u8) {}
+            )
+        )
+        .unwrap()
+        .to_string()
+        .contains("f_größe"));
+    }
+
+    #[test]
+    fn invalid_quoted_headers() {
+        assert_eq!(
+            diagnostics(r#"["an input"] [1]"#, "fn f(a: u8) {}"),
+            [(
+                "A quoted column name must be an identifier, such as `\"input\"`.".to_string(),
+                r#""an input""#.to_string(),
+            )]
+        );
+        assert_eq!(
+            diagnostics(r#"["name"!, a] [x, 1]"#, "fn f(a: u8) {}"),
+            [(
+                "Column markers must be identifiers, such as `name!`.".to_string(),
+                r#""name""#.to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn fixtures_count_towards_arity() {
+        assert_eq!(
+            errors(
+                quote!(fixtures = [db], [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["Number of parameters does not match the test function's arity."]
+        );
+        assert_eq!(
+            errors(
+                quote!(fixtures = [], [a][1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["The `fixtures` option needs at least one fixture."]
+        );
+    }
+
+    #[test]
+    fn misplaced_check_columns() {
+        assert_eq!(
+            errors(
+                quote!(assert = eq, [a, check!, b] [1, |_| (), 2]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["A `check!` column cannot be used with the `assert` option."]
+        );
+        assert_eq!(
+            errors(
+                quote!([a, check!, check!] [1, |_| (), |_| ()]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["Only one column may be marked with `check!`."]
+        );
+    }
+
+    #[test]
+    fn expressions_without_parameters() {
+        assert_eq!(
+            errors(quote!(expr = a, case_name, [a, b] [1, 1]), quote!(fn f(a: u8) {})),
+            [
+                "The `expr` option cannot be used with an option which passes extra arguments to the test function.",
+                "The test function is only used for its name when the `expr` option is given, so it cannot have parameters.",
+            ]
+        );
+    }
+
+    #[test]
+    fn mismatched_spreads() {
+        assert_eq!(
+            diagnostics("[spread!(a, b), c] [(1, 2, 3), 4]", "fn f(a: u8, b: u8, c: u8) {}"),
+            [(
+                "This tuple has 3 elements, but the `spread!(a, b)` column spreads it across 2 parameters.".to_string(),
+                "(1, 2, 3)".to_string(),
+            )]
+        );
+        assert_eq!(
+            errors(
+                quote!([spread!(a, b)][(1, 2)]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["Number of parameters does not match the test function's arity."]
+        );
+    }
+
+    #[test]
+    fn malformed_notes() {
+        assert_eq!(
+            errors(
+                quote!([a] #[note("text")] [1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["A note must be written as `#[note = \"...\"]`."]
+        );
+        assert_eq!(
+            errors(
+                quote!(no_std, [a] #[note = "text"] [1]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["`#[note]` cannot be used with the `no_std` option."]
+        );
+    }
+
+    #[test]
+    fn short_rows_with_defaults() {
+        assert_eq!(
+            diagnostics(
+                "[a, b = 2, c = 3] [1, 2] [] [4, 5, 6]",
+                "fn f(a: u8, b: u8, c: u8) {}"
+            ),
+            [(
+                "Expected between 1 and 3 arguments (a, b, c) but found 0.".to_string(),
+                "[]".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn option_errors_are_combined() {
+        let errors = errors(
+            quote!(inline, single_test, expose_names, [input][1]),
+            quote!(
+                fn generic<T>(input: u8) {}
+            ),
+        );
+        assert_eq!(
+            errors,
+            [
+                "The `inline` option cannot be used with a generic test function.",
+                "The `inline` option cannot be used with the `single_test` option.",
+                "The `expose_names` option cannot be used with the `single_test` option.",
+            ]
+        );
+    }
+
+    #[test]
+    fn option_and_table_errors_are_combined() {
+        let errors = errors(
+            quote!(no_helper, [input, input] [1, 2] [1, 2]),
+            quote!(
+                fn repeated(input: u8) {}
+            ),
+        );
+        assert_eq!(
+            errors,
+            [
+                "The `no_helper` option cannot be used with a test which has no `call` option to give the function which the cases call.",
+                "Number of parameters does not match the test function's arity.",
+                "This column name is used by another column.",
+                "This row is the same as another row. Use the `allow_duplicates` option if this is intended.",
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_cells() {
+        assert_eq!(
+            table_error("[a, b] [1, 2] [3, +]"),
+            "Could not parse cell 2 of row 2 as an expression: expected expression",
+        );
+        assert_eq!(
+            table_error("[a, b] [1 2, 3]"),
+            "Could not parse cell 1 of row 1 as an expression. Cells are separated by commas.",
+        );
+        assert_eq!(
+            table_error("[ty!, b] [u8 u8, 1]"),
+            "Could not parse cell 1 of row 1 as a type. Cells are separated by commas.",
+        );
+    }
+
+    #[test]
+    fn empty_tables() {
+        let message = "This table has no columns. A test without parameters should use `#[test]` instead, with a loop if it should run more than once.";
+        assert_eq!(table_error("[]"), message);
+        assert_eq!(table_error("[] [] []"), message);
+        assert_eq!(table_error("repeat = 3, [] []"), message);
+        assert_eq!(
+            errors(
+                quote!([a][]),
+                quote!(
+                    fn f(a: u8) {}
+                )
+            ),
+            ["Expected 1 argument (a) but found 0."]
+        );
+    }
+
+    #[test]
+    fn optional_columns_with_defaults() {
+        assert_eq!(
+            table_error("[a, b?: u8 = 1] [1]"),
+            "A column marked with `?` already defaults to `Default::default()`, so it cannot be given another default cell."
+        );
+    }
+
+    #[test]
+    fn malformed_matrices() {
+        assert_eq!(
+            table_error("matrix [a, b] a in 0..2"),
+            "The `b` column needs a range, such as `b in 0..3`."
+        );
+        assert_eq!(
+            table_error("matrix [a] a in 0..2, c in 0..2"),
+            "This matrix has no `c` column."
+        );
+        assert_eq!(
+            table_error("matrix [a] a in 0..2, a in 1..2"),
+            "The `a` column has already been given a range."
+        );
+        assert_eq!(
+            table_error("matrix [name!, a] a in 0..2"),
+            "Only plain columns can be used in a `matrix`, as each one is given a range."
+        );
+        assert_eq!(
+            table_error("matrix [a] a in x"),
+            "Expected a range, such as `0..5`."
+        );
+    }
+
+    #[test]
+    fn malformed_named_cells() {
+        assert_eq!(
+            table_error("[a, b] { a = 1, b = ; }"),
+            "Could not parse the `b` cell of row 1 as an expression: expected expression",
+        );
     }
 }
@@ -0,0 +1,48 @@
+#![cfg(feature = "bench")]
+#![feature(test)]
+
+extern crate test;
+
+use test_with_parameters::test_with_parameters;
+
+#[test_with_parameters(
+    bench,
+    [ input , expected ]
+    [ 10    , 55       ]
+    [ 20    , 6765     ]
+)]
+fn fibonacci(input: u64, expected: u64) {
+    let (mut a, mut b) = (0, 1);
+    for _ in 0..input {
+        (a, b) = (b, a + b);
+    }
+    assert_eq!(a, expected)
+}
+
+#[test_with_parameters(
+    bench,
+    [ input ]
+    [ "1"   ]
+    [ "23"  ]
+)]
+fn parses(input: &str) -> Result<(), std::num::ParseIntError> {
+    input.parse::<u8>()?;
+    Ok(())
+}
+
+test_with_parameters::parameter_table! {
+    squares =
+    [ input , expected ]
+    [ 3     , 9        ]
+    [ 12    , 144      ]
+}
+
+#[test_with_parameters(use squares)]
+fn squares(input: u32, expected: u32) {
+    assert_eq!(input * input, expected)
+}
+
+#[test_with_parameters(bench, use squares)]
+fn bench_squares(input: u32, expected: u32) {
+    assert_eq!(input.pow(2), expected)
+}
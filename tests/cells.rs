@@ -0,0 +1,610 @@
+use std::collections::HashMap;
+use test_with_parameters::test_with_parameters;
+
+#[derive(Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test_with_parameters(
+    [ input                , expected ]
+    [ { let x = 1; x + 2 } , 3        ]
+    [ {
+        let mut total = 0;
+        for i in 0..3 {
+            total += i;
+        }
+        total
+      }                    , 3        ]
+)]
+fn block_cells(input: i32, expected: i32) {
+    assert_eq!(input, expected)
+}
+
+#[test_with_parameters(
+    [ "input" , "expected" ]
+    [ (1, 1)  , 2          ]
+    [ (2, 2)  , 4          ]
+)]
+fn quoted_headers(input: (usize, usize), expected: usize) {
+    assert_eq!(input.0 + input.1, expected)
+}
+
+#[test_with_parameters(
+    strict,
+    [ "input" , expected: u8 , "limit" = 10 ]
+    [ 1       , 2                           ]
+    [ 9       , 9            , 9            ]
+)]
+fn mixed_headers(input: u8, expected: u8, limit: u8) {
+    assert_eq!((input + 1).min(limit), expected)
+}
+
+#[test_with_parameters(
+    [ input  , expected ]
+    [ (1, 2) , Point {
+        x: 1,
+        y: 2,
+      }                 ]
+    [ (3, 4) , Point { x: 3, y: 4 } ]
+)]
+fn struct_literal_cells(input: (i32, i32), expected: Point) {
+    let (x, y) = input;
+    assert_eq!(Point { x, y }, expected)
+}
+
+#[derive(Default)]
+struct Options {
+    verbose: bool,
+    depth: usize,
+    name: &'static str,
+}
+
+#[test_with_parameters(
+    [ options                                         , expected    ]
+    [ Options::default()                              , "0"         ]
+    [ Options { verbose: true, ..Default::default() } , "0 verbose" ]
+    [ Options {
+        depth: 2,
+        name: "a, b",
+        ..Options::default()
+      }                                               , "2 a, b"    ]
+)]
+fn struct_update_cells(options: Options, expected: &str) {
+    let mut description = options.depth.to_string();
+    if options.verbose {
+        description.push_str(" verbose");
+    }
+    if !options.name.is_empty() {
+        description.push(' ');
+        description.push_str(options.name);
+    }
+    assert_eq!(description, expected)
+}
+
+#[test_with_parameters(
+    [ map                                                          , len ]
+    [ HashMap::<u8, u8>::new()                                     , 0   ]
+    [ vec![(1, 2), (3, 4)].into_iter().collect::<HashMap<_, _>>() , 2   ]
+)]
+fn nested_commas(map: HashMap<u8, u8>, len: usize) {
+    assert_eq!(map.len(), len)
+}
+
+#[test_with_parameters(
+    [ values             , label                         , expected ]
+    [ vec![1, 2, 3]      , format!("{}, {}", 1, 2)       , 6        ]
+    [ vec![4; 2]         , concat!("a", ",", "b").into() , 8        ]
+)]
+fn macro_cells(values: Vec<u8>, label: String, expected: u8) {
+    assert!(label.contains(','));
+    assert_eq!(values.iter().sum::<u8>(), expected)
+}
+
+/// Doubles its input.
+#[test_with_parameters(
+    doc_table,
+    [ input , expected ]
+    [ 1     , 2        ]
+    [ 2     , 4        ]
+)]
+fn documented_cells(input: u8, expected: u8) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test_with_parameters(
+    [ input: u8 , expected ]
+    [ 1         , 2        ]
+    [ input: u8 , expected ]
+    [ 2         , 4        ]
+    [ input: u8 , expected ]
+    [ 3         , 6        ]
+)]
+fn repeated_headers(input: u8, expected: u8) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test]
+fn repeated_headers_are_not_cases() {
+    let _ = repeated_headers_case0;
+    let _ = repeated_headers_case1;
+    let _ = repeated_headers_case2;
+}
+
+#[test_with_parameters(
+    [ name!  , ty! , input  , expected: (i32, i32) ]
+    [ tuple  , i32 , (1, 2) , (1, 2)               ]
+    { expected = (3, 4), name! = swapped, input = (3, 4), ty! = i64 }
+    {
+        name! = multi_line,
+        input = (5, 6),
+        ty! = u8,
+        expected = (5, 6),
+    }
+)]
+fn named_cells<T>(input: (i32, i32), expected: (i32, i32)) {
+    let _ = std::marker::PhantomData::<T>;
+    assert_eq!(input, expected)
+}
+
+#[test]
+fn named_cells_are_reordered() {
+    let _ = named_cells_tuple;
+    let _ = named_cells_swapped;
+    let _ = named_cells_multi_line;
+}
+
+#[test_with_parameters(
+    rows {
+        input = (1, 1) expected = 2;
+        expected = 4 input = (2, 2);
+        #[should_panic]
+        input=(2,2) expected=-5;
+    }
+)]
+fn block_rows(input: (i32, i32), expected: i32) {
+    let (left, right) = input;
+    assert_eq!(left + right, expected)
+}
+
+#[test_with_parameters(
+    rows {
+        name! = small input = 1 large = input == 5;
+        name! = big input = 5 large = true
+    }
+)]
+fn named_block_rows(input: u8, large: bool) {
+    assert_eq!(input > 1, large)
+}
+
+#[test]
+fn block_rows_are_cases() {
+    let _ = block_rows_case2;
+    let _ = named_block_rows_small;
+    let _ = named_block_rows_big;
+}
+
+fn line_ending(lines: &[&str]) -> String {
+    let ending = if cfg!(windows) { "\r\n" } else { "\n" };
+    lines.join(ending)
+}
+
+#[test_with_parameters(
+    assert = eq,
+    [ input      , platform!(expected)   ]
+    [ &["a"]     , "a"                   ]
+    [ &["a", "b"], "a\nb" | "a\r\nb"     ]
+    [ &["a", ""] , "a\n" | "a\r\n"       ]
+)]
+fn platform_cells(input: &[&str]) -> String {
+    line_ending(input)
+}
+
+#[test_with_parameters(
+    [ input      , flags: u8 , expected                                       ]
+    [ &["a", "b"], 1 | 2     , cfg!(windows).then(|| "a\r\nb").unwrap_or("a\nb") ]
+)]
+fn cfg_cells(input: &[&str], flags: u8, expected: &str) {
+    assert_eq!(flags, 3);
+    assert_eq!(line_ending(input), expected)
+}
+
+#[test_with_parameters(
+    [ range!(input) , expected ]
+    [ 0..3          , false    ]
+    [ 3..=4         , true     ]
+    [ -2..0         , false    ]
+)]
+fn range_cells(input: i32, expected: bool) {
+    assert_eq!(input >= 3, expected)
+}
+
+#[test_with_parameters(
+    [ range!(input: u8) , range!(next) ]
+    [ 1u8..=3           , 2..=4        ]
+)]
+fn zipped_ranges(input: u8, next: u8) {
+    assert_eq!(input + 1, next)
+}
+
+#[test]
+fn ranges_expand_into_cases() {
+    let _ = range_cells_case0_0;
+    let _ = range_cells_case0_2;
+    let _ = range_cells_case1_1;
+    let _ = range_cells_case2_1;
+}
+
+#[test_with_parameters(
+    matrix [ a , b: u8 ] a in 0..3, b in 1..=2
+)]
+fn matrix_cells(a: u8, b: u8) {
+    assert_eq!(a.max(b).min(a), a)
+}
+
+#[test]
+fn matrices_expand_into_cases() {
+    let _ = matrix_cells_case0_0;
+    let _ = matrix_cells_case0_1;
+    let _ = matrix_cells_case2_1;
+}
+
+#[test_with_parameters(
+    [ input , expected , tolerance: f64 = 0.001 ]
+    [ 0.5   , 0.25                              ]
+    [ 0.1   , 0.01     , 0.01                   ]
+)]
+fn default_cells(input: f64, expected: f64, tolerance: f64) {
+    assert!((input * input - expected).abs() < tolerance)
+}
+
+#[test_with_parameters(
+    [ text , width = 0 , fill = ' ' , expected = "" ]
+    [ "a"                                           ]
+    [ "b"  , 3                                      ]
+    [ "c"  , 3         , '-'                        ]
+    [ "d"  , 2         , '*'        , "*d"          ]
+)]
+fn trailing_default_cells(text: &str, width: usize, fill: char, expected: &str) {
+    let padded = format!(
+        "{}{}",
+        fill.to_string().repeat(width.saturating_sub(1)),
+        text
+    );
+    assert!(expected.is_empty() || padded == expected);
+    assert_eq!(padded.len(), width.max(1))
+}
+
+#[test_with_parameters(
+    [ path => std::path::PathBuf::from , len: usize => |n: u8| n.into() , expected ]
+    [ "a/b.txt"                        , 2                              , "b.txt"  ]
+    [ "c.txt"                          , 1                              , "c.txt"  ]
+)]
+fn transformed_cells(path: std::path::PathBuf, len: usize, expected: &str) {
+    assert_eq!(path.components().count(), len);
+    assert_eq!(path.file_name().unwrap(), expected)
+}
+
+#[test_with_parameters(
+    single_test, homogeneous,
+    [ expected , input: String = "x" => String::from ]
+    [ 2        , "ab"                                ]
+    [ 1                                              ]
+)]
+fn transformed_cells_in_single_tests(expected: usize, input: String) {
+    assert_eq!(input.len(), expected)
+}
+
+#[test_with_parameters(
+    [ into!(name) , into!(count) , expected ]
+    [ "ab"        , 2u8          , 2        ]
+    [ "abc"       , 3u8          , 3        ]
+)]
+fn converted_cells(name: String, count: u64, expected: usize) {
+    assert_eq!(name.len(), expected);
+    assert_eq!(count, expected as u64)
+}
+
+#[test_with_parameters(
+    [ input , expected , label?: String , flags?: (bool, u8) ]
+    [ 1     , 2                                              ]
+    [ 2     , 4        , "two".into()                        ]
+    [ 3     , 6        , "three".into() , (true, 1)          ]
+    { flags = (false, 2), input = 4, expected = 8 }
+)]
+fn optional_cells(input: u8, expected: u8, label: String, flags: (bool, u8)) {
+    assert_eq!(input * 2, expected);
+    assert!(label.is_empty() || input > 1);
+    assert!(flags == (false, 0) || input > 2)
+}
+
+#[test_with_parameters(
+    [ name! , offset = 1 , input , expected ]
+    { name! = one, input = 1, expected = 2 }
+    { name! = two, offset = 2, input = 1, expected = 3 }
+)]
+fn named_default_cells(offset: u8, input: u8, expected: u8) {
+    assert_eq!(input + offset, expected)
+}
+
+#[test_with_parameters(
+    [ operation              , input  , expected ]
+    [ |a, b| a + b           , (1, 2) , 3        ]
+    [ |a: u8, b: u8| a * b   , (2, 3) , 6        ]
+    [ |a, _| a               , (4, 5) , 4        ]
+    [ u8::max                , (6, 7) , 7        ]
+)]
+fn closure_cells(operation: fn(u8, u8) -> u8, input: (u8, u8), expected: u8) {
+    assert_eq!(operation(input.0, input.1), expected)
+}
+
+#[test_with_parameters(
+    [ operation                        , expected ]
+    [ |x| x + 1                        , 4        ]
+    [ move |x| { let y = x * 2; y - 1 } , 5        ]
+)]
+fn generic_closure_cells(operation: impl Fn(u8) -> u8, expected: u8) {
+    assert_eq!(operation(3), expected)
+}
+
+#[test_with_parameters(
+    show_cells,
+    [ input  , expected ]
+    [ (1, 1) , 2        ]
+    #[should_panic(expected = "input=(1, 2) expected=4")]
+    [ (1, 2) , 4        ]
+)]
+fn shown_cells(input: (u8, u8), expected: u8) {
+    assert_eq!(input.0 + input.1, expected)
+}
+
+#[test_with_parameters(
+    show_cells, assert = eq,
+    [ input , expected ]
+    [ "1"   , Ok(1)    ]
+    #[should_panic(expected = "input=\"one\" expected=Ok(1)")]
+    [ "one" , Ok(1)    ]
+)]
+fn shown_asserted_cells(input: &str) -> Result<u8, std::num::ParseIntError> {
+    input.parse()
+}
+
+static LIMIT: &u8 = &7;
+const ENABLED: bool = true;
+
+#[test_with_parameters(
+    [ number , negated  , limit  , borrowed , expected ]
+    [ -1     , !ENABLED , *LIMIT , &2       , -7       ]
+    [ -(-3)  , !ENABLED , *LIMIT , &LIMIT   , 21       ]
+    [ - 2    , !ENABLED , 2      , &7       , -4       ]
+    [ -2     , ENABLED  , 2      , &7       , 4        ]
+)]
+fn prefix_operator_cells(number: i32, negated: bool, limit: u8, borrowed: &u8, expected: i32) {
+    assert!(*borrowed <= 7);
+    let product = number * i32::from(limit);
+    assert_eq!(if negated { -product } else { product }, expected)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    const OPPOSITE_OF_NORTH: Direction = Direction::South;
+
+    fn turn(self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+}
+
+#[test_with_parameters(
+    [ direction: Direction         , expected               ]
+    [ Direction::North             , Direction::East        ]
+    [ crate::Direction::East       , self::Direction::South ]
+    [ Direction::OPPOSITE_OF_NORTH , Direction::West        ]
+    [ <Direction>::West            , Direction::North       ]
+)]
+fn path_cells(direction: Direction, expected: Direction) {
+    assert_eq!(direction.turn(), expected)
+}
+
+#[test_with_parameters(
+    [ name!   , direction        ]
+    [ north   , Direction::North ]
+    [ "south" , Direction::South ]
+)]
+fn named_path_cells(direction: Direction) {
+    assert_eq!(direction.turn().turn().turn().turn(), direction)
+}
+
+#[test_with_parameters(
+    [ x , doubled , label                  ]
+    [ 4 , x * 2   , format!("{}", doubled) ]
+    [ 5 , x + x   , format!("{}", x * 2)   ]
+)]
+fn earlier_cells(x: u8, doubled: u8, label: String) {
+    assert_eq!(x * 2, doubled);
+    assert_eq!(label, doubled.to_string())
+}
+
+#[test_with_parameters(
+    assert = eq,
+    [ input , expected            ]
+    [ "1"   , input.parse::<u8>() ]
+    [ "one" , input.parse::<u8>() ]
+)]
+fn earlier_asserted_cells(input: &str) -> Result<u8, std::num::ParseIntError> {
+    input.parse()
+}
+
+#[test_with_parameters(
+    show_cells,
+    [ tuple!(left, right) , expected ]
+    [ 1      , 2          , 3        ]
+    [ -1     , 1          , 0        ]
+    #[should_panic(expected = "tuple!(left, right)=(2, 2) expected=5")]
+    [ 2      , 2          , 5        ]
+)]
+fn tuple_cells((left, right): (i8, i8), expected: i8) {
+    assert_eq!(left + right, expected)
+}
+
+#[test_with_parameters(
+    [ name! , tuple!(x, y, z) , point: (u8, u8, u8) ]
+    [ zero  , 0 , 0 , 0       , (0, 0, 0)           ]
+    [ one   , 1 , 0 , 1       , (1, 0, 1)           ]
+)]
+fn tuple_cells_with_types(coordinates: (u8, u8, u8), point: (u8, u8, u8)) {
+    assert_eq!(coordinates, point)
+}
+
+const fn compute_expected(input: u32) -> u32 {
+    input * input + 1
+}
+
+const fn clamp(value: u32, low: u32, high: u32) -> u32 {
+    if value < low {
+        low
+    } else if value > high {
+        high
+    } else {
+        value
+    }
+}
+
+#[test_with_parameters(
+    [ input          , expected                                         ]
+    [ 3              , compute_expected(3)                              ]
+    [ clamp(9, 0, 4) , compute_expected(clamp(9, 0, 4))                 ]
+    [ clamp(0, 2, 4) , { const VALUE: u32 = compute_expected(2); VALUE } ]
+)]
+fn const_fn_cells(input: u32, expected: u32) {
+    assert_eq!(input * input + 1, expected)
+}
+
+#[test_with_parameters(
+    assert = eq,
+    [ value , low , high , expected            ]
+    [ 5     , 0   , 3    , clamp(5, 0, 3)      ]
+    [ 1     , 2   , 3    , clamp(1, 2, 3)      ]
+    [ 2     , 0   , 3    , compute_expected(1) ]
+)]
+fn const_fn_asserted_cells(value: u32, low: u32, high: u32) -> u32 {
+    clamp(value, low, high)
+}
+
+const ORIGIN: (i8, i8, i8) = (0, 0, 0);
+
+#[test_with_parameters(
+    show_cells,
+    [ spread!(x, y, z) , expected ]
+    [ (1, 2, 3)        , 6        ]
+    [ ORIGIN           , 0        ]
+    #[should_panic(expected = "spread!(x, y, z)=(1, 1, 1) expected=4")]
+    [ (1, 1, 1)        , 4        ]
+)]
+fn spread_cells(x: i8, y: i8, z: i8, expected: i8) {
+    assert_eq!(x + y + z, expected)
+}
+
+#[test_with_parameters(
+    strict,
+    [ spread!(left, right) , label    ]
+    [ ("a", 1)             , "a1"     ]
+    [ ("b", 2)             , "b2"     ]
+)]
+fn strict_spread_cells(left: &str, right: u8, label: &str) {
+    assert_eq!(format!("{}{}", left, right), label)
+}
+
+#[test_with_parameters(
+    [ by_ref!(primes)      , point                  , count        ]
+    [ [2, 3, 5, 7, 11, 13] , &Point { x: 1, y: 2 }  , primes.len() ]
+    [ [17, 19, 23]         , &Point { x: 3, y: 4 }  , 3            ]
+)]
+fn by_ref_cells(primes: &[u32], point: &Point, count: usize) {
+    assert_eq!(primes.len(), count);
+    assert!(point.x < point.y)
+}
+
+#[test_with_parameters(
+    [ by_ref!(point)          , expected ]
+    [ Point { x: 1, y: 2 }    , 3        ]
+    [ Point { x: -1, y: 1 }   , 0        ]
+)]
+fn static_cells(point: &'static Point, expected: i32) {
+    assert_eq!(point.x + point.y, expected)
+}
+
+#[test_with_parameters(
+    [ by_ref!(text) , expected ]
+    [ "abc"         , 3        ]
+)]
+fn static_str_cells(text: &str, expected: usize) {
+    assert_eq!(text.len(), expected)
+}
+
+#[test_with_parameters(
+    [ by_ref!(r#type) , expected ]
+    [ "abc"           , 3        ]
+)]
+fn raw_by_ref_cells(r#type: &str, expected: usize) {
+    assert_eq!(r#type.len(), expected)
+}
+
+#[test_with_parameters(
+    homogeneous,
+    [ x   , doubled     , label     , value: &dyn std::fmt::Debug , expected ]
+    [ 1   , x * 2       , "one"     , &1                          , 2        ]
+    [ 2u8 , 4           , "two"     , &"two"                      , 4        ]
+    [ 3   , x + x       , "three"   , &'3'                        , 6        ]
+)]
+fn homogeneous_cells(x: u8, doubled: u8, label: &str, value: &dyn std::fmt::Debug, expected: u8) {
+    assert!(!label.is_empty());
+    assert!(!format!("{:?}", value).is_empty());
+    assert_eq!(x * 2, doubled);
+    assert_eq!(doubled, expected)
+}
+
+#[test_with_parameters(
+    homogeneous,
+    [ value     , expected ]
+    [ 1         , "1"      ]
+    [ "two"     , "two"    ]
+)]
+fn homogeneous_generic_cells(value: impl std::fmt::Display, expected: &str) {
+    assert_eq!(value.to_string(), expected)
+}
+
+#[test_with_parameters(
+    homogeneous,
+    [ items      , label , expected ]
+    [ Vec::new() , None  , 0        ]
+    [ vec![1, 2] , None  , 2        ]
+)]
+fn homogeneous_cells_of_parameter_types(items: Vec<u8>, label: Option<&str>, expected: usize) {
+    assert!(label.is_none());
+    assert_eq!(items.len(), expected)
+}
+
+fn offset() -> u32 {
+    1
+}
+
+#[test_with_parameters(
+    [ input              , offset ]
+    [ self::offset() + 1 , 1      ]
+)]
+fn items_named_like_later_columns(input: u32, offset: u32) {
+    assert_eq!(input - offset, 1)
+}
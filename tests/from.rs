@@ -0,0 +1,28 @@
+use test_with_parameters::test_with_parameters;
+
+const ADDITIONS: [((usize, usize), usize); 2] = [((1, 1), 2), ((2, 2), 4)];
+static WORDS: &[&str] = &["one", "two", "three"];
+
+#[test_with_parameters(from = ADDITIONS, count = 2)]
+fn tuple_elements(input: (usize, usize), expected: usize) {
+    let (left, right) = input;
+    assert_eq!(left + right, expected)
+}
+
+#[test_with_parameters(from = WORDS, count = 3)]
+fn single_elements(word: &str) {
+    assert!(word.len() >= 3)
+}
+
+#[test_with_parameters(from = ADDITIONS, count = 2, case_name)]
+fn elements_with_details(case_name: &str, (left, right): (usize, usize), expected: usize) {
+    assert!(case_name.starts_with("elements_with_details_case"));
+    assert_eq!(left + right, expected)
+}
+
+#[test]
+fn cases_from_elements() {
+    let _ = tuple_elements_case0;
+    let _ = tuple_elements_case1;
+    let _ = single_elements_case2;
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "golden")]
+
+use std::process::Command;
+use test_with_parameters::test_with_parameters;
+
+#[test_with_parameters(
+    golden = "tests/golden/values.txt",
+    [ input ]
+    [ 1     ]
+    [ 2     ]
+)]
+fn doubles(input: u8) -> u8 {
+    input * 2
+}
+
+#[test_with_parameters(
+    golden = "tests/golden/values.txt", module,
+    [ input ]
+    [ "a b" ]
+)]
+fn words(input: &str) -> Vec<&str> {
+    input.split(' ').collect()
+}
+
+/// Runs the given ignored cases of this test binary in a process of their own,
+/// with or without `UPDATE_GOLDEN`, and returns whether they passed.
+fn run_ignored(cases: &[&str], update: bool) -> bool {
+    let mut command = Command::new(std::env::current_exe().unwrap());
+    command.args(["--ignored", "--exact"]).args(cases);
+    if update {
+        command.env("UPDATE_GOLDEN", "1");
+    } else {
+        command.env_remove("UPDATE_GOLDEN");
+    }
+    command.output().unwrap().status.success()
+}
+
+// The file of these cases deliberately holds the wrong values, so they are
+// only run by `reports_mismatches`, which never updates it.
+#[test_with_parameters(
+    golden = "tests/golden/mismatched.txt",
+    [ input ]
+    #[should_panic(expected = "does not match the golden file")]
+    [ 3     ]
+    #[should_panic(expected = "has no value for `mismatched_case1`")]
+    [ 4     ]
+)]
+#[ignore = "run by `reports_mismatches`"]
+fn mismatched(input: u8) -> u8 {
+    input * 2
+}
+
+#[test]
+fn reports_mismatches() {
+    assert!(run_ignored(
+        &["mismatched_case0", "mismatched_case1"],
+        false
+    ));
+}
+
+#[test_with_parameters(
+    golden = "target/golden/updated.txt",
+    [ input ]
+    [ 1     ]
+    [ 2     ]
+)]
+#[ignore = "run by `updates_golden_files`"]
+fn updated(input: u8) -> u8 {
+    input * 2
+}
+
+#[test]
+fn updates_golden_files() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("target/golden/updated.txt");
+    let _ = std::fs::remove_file(&path);
+    let cases = ["updated_case0", "updated_case1"];
+    assert!(!run_ignored(&cases, false));
+    assert!(run_ignored(&cases, true));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "updated_case0: 2\nupdated_case1: 4\n"
+    );
+    assert!(run_ignored(&cases, false));
+}
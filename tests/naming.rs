@@ -0,0 +1,361 @@
+use test_with_parameters::test_with_parameters;
+
+#[test_with_parameters(
+    naming = hash, allow_duplicates,
+    [ input , expected ]
+    [ 1     , 2        ]
+    [ 2     , 4        ]
+    [ 2     , 4        ]
+)]
+fn hashed_names(input: u8, expected: u8) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test_with_parameters(
+    naming = hash,
+    [ input , expected ]
+    [ 3     , 6        ]
+    [ 2     , 4        ]
+)]
+fn inserted_rows(input: u8, expected: u8) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test]
+fn hashed_names_depend_only_on_cells() {
+    let _ = hashed_names_case_c00af486;
+    let _ = hashed_names_case_405c81af;
+    let _ = hashed_names_case_405c81af_1;
+    let _ = inserted_rows_case_405c81af;
+}
+
+#[test_with_parameters(
+    case_name,
+    [ name!  , input ]
+    [ first  , 1     ]
+    [ second , 2     ]
+)]
+fn passes_case_names(case_name: &str, input: u8) {
+    let expected = ["passes_case_names_first", "passes_case_names_second"];
+    assert_eq!(case_name, expected[usize::from(input) - 1])
+}
+
+#[test_with_parameters(
+    case_name, single_test, setup = { 10 },
+    [ input ]
+    [ 0     ]
+    [ 1     ]
+)]
+fn passes_case_names_to_single_tests(case_name: &str, offset: usize, input: usize) {
+    let expected = [
+        "passes_case_names_to_single_tests_case0",
+        "passes_case_names_to_single_tests_case1",
+    ];
+    assert_eq!(offset, 10);
+    assert_eq!(case_name, expected[input])
+}
+
+#[test_with_parameters(
+    case_name, single_test = both,
+    [ input ]
+    [ 0     ]
+    [ 1     ]
+)]
+fn separate_and_single_tests(case_name: &str, input: usize) {
+    assert!(case_name.ends_with(&input.to_string()))
+}
+
+#[test_with_parameters(
+    module, single_test = both,
+    [ input ]
+    [ 1     ]
+)]
+fn separate_and_single_module_tests(input: usize) {
+    assert!(input > 0)
+}
+
+#[test]
+fn both_kinds_of_test_are_generated() {
+    let _ = separate_and_single_tests_case0;
+    let _ = separate_and_single_tests_case1;
+    let _ = separate_and_single_tests_all;
+}
+
+#[test_with_parameters(
+    expose_index, start = 1,
+    [ input ]
+    [ 1     ]
+    skip [ 2 ]
+    [ 3     ]
+)]
+fn passes_indices(index: usize, input: usize) {
+    assert_eq!(index, input)
+}
+
+#[test_with_parameters(
+    case_name, expose_index, single_test,
+    [ input ]
+    [ 0     ]
+    [ 1     ]
+)]
+fn passes_indices_to_single_tests(case_name: &str, index: usize, input: usize) {
+    assert_eq!(index, input);
+    assert!(case_name.ends_with(&index.to_string()))
+}
+
+#[test_with_parameters(
+    single_test,
+    [ label , result , cases: &[u8] ]
+    [ "a"   , 1      , &[1]         ]
+    [ "b"   , 2      , &[2, 2]      ]
+)]
+fn single_tests_with_columns_named_like_their_loop(
+    label: &str,
+    result: usize,
+    cases: &[u8],
+) -> Result<(), String> {
+    assert_eq!(label.len(), 1);
+    match cases.len() == result {
+        true => Ok(()),
+        false => Err(format!("The case `{}` has the wrong length.", label)),
+    }
+}
+
+#[test_with_parameters(
+    case_name,
+    [ name!  , input ]
+    [ short  , 1     ]
+    #[tag(slow)]
+    [ long   , 2     ]
+    #[tag(slow, network)]
+    #[tag(flaky)]
+    [ remote , 3     ]
+)]
+fn tagged_rows(case_name: &str, input: u8) {
+    assert!(input > 0);
+    assert!(case_name.starts_with("tagged_rows_"))
+}
+
+#[test]
+fn tags_are_added_to_names() {
+    let _ = tagged_rows_short;
+    let _ = tagged_rows_slow_long;
+    let _ = tagged_rows_slow_network_flaky_remote;
+}
+
+#[test_with_parameters(
+    sorted, naming = hash,
+    [ input , expected ]
+    [ 3     , 6        ]
+    [ 1     , 2        ]
+    [ 2     , 4        ]
+)]
+fn sorted_cases(input: u8, expected: u8) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test_with_parameters(
+    module,
+    [ name!   , input ]
+    [ "match" , 1     ]
+    [ "type"  , 2     ]
+    [ "self"  , 3     ]
+    [ "1foo"  , 4     ]
+    [ plain   , 5     ]
+)]
+fn keyword_cases(input: u8) {
+    assert!(input > 0)
+}
+
+#[test_with_parameters(
+    [ name!  , input ]
+    [ r#type , 1     ]
+    [ r#loop , 2     ]
+)]
+fn raw_names(input: u8) {
+    assert!(input > 0)
+}
+
+#[test]
+fn raw_names_are_used_without_their_prefix() {
+    let _ = raw_names_type;
+    let _ = raw_names_loop;
+}
+
+#[test_with_parameters(
+    case_name,
+    [ name!     , input ]
+    [ AddsZero  , 0     ]
+    [ "AddsOne" , 1     ]
+)]
+fn named_in_camel_case(case_name: &str, input: u8) {
+    let expected = [
+        "named_in_camel_case_AddsZero",
+        "named_in_camel_case_addsone",
+    ];
+    assert_eq!(case_name, expected[usize::from(input)])
+}
+
+#[test_with_parameters(
+    [ input , expected ]
+    [ 1     , 2        ]
+    [ 2     , 4        ]
+)]
+#[test_with_parameters(
+    start = 5,
+    [ input , expected ]
+    [ 3     , 6        ]
+)]
+#[test_with_parameters(
+    [ name! , input , expected ]
+    [ large , 100   , 200      ]
+)]
+fn stacked_tables(input: u8, expected: u8) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test]
+fn stacked_tables_are_numbered_in_order() {
+    let _ = stacked_tables_case0;
+    let _ = stacked_tables_case1;
+    let _ = stacked_tables_case5;
+    let _ = stacked_tables_large;
+}
+
+#[test_with_parameters(
+    expose_names,
+    [ name!  , input ]
+    [ first  , 1     ]
+    #[cfg(not(test))]
+    [ hidden , 2     ]
+    [ third  , 3     ]
+)]
+fn exposed_names(input: u8) {
+    assert!(input > 0)
+}
+
+#[test_with_parameters(
+    expose_names, module,
+    [ input ]
+    [ 1     ]
+    [ 2     ]
+)]
+fn exposed_module_names(input: u8) {
+    assert!(input > 0)
+}
+
+#[test]
+fn names_are_exposed() {
+    assert_eq!(
+        exposed_names_cases(),
+        ["exposed_names_first", "exposed_names_third"]
+    );
+    assert_eq!(
+        exposed_module_names_cases(),
+        ["exposed_module_names::case0", "exposed_module_names::case1"]
+    );
+}
+
+#[test_with_parameters(
+    [ input ]
+    [ 1     ]
+    #[cfg(not(test))]
+    [ 2     ]
+)]
+#[test_with_parameters(
+    expose_names, expose_count,
+    [ name! , input ]
+    [ large , 100   ]
+)]
+fn exposed_stacked_names(input: u8) {
+    assert!(input > 0)
+}
+
+#[test]
+fn stacked_names_are_exposed() {
+    assert_eq!(
+        exposed_stacked_names_cases(),
+        ["exposed_stacked_names_case0", "exposed_stacked_names_large"]
+    );
+    assert_eq!(EXPOSED_STACKED_NAMES_CASE_COUNT, 2);
+}
+
+#[test_with_parameters(
+    case_name, prefix = "fast_", suffix = "_v2",
+    [ name!  , input ]
+    [ small  , 1     ]
+    #[tag(slow)]
+    [ large  , 2     ]
+)]
+fn affixed_names(case_name: &str, input: u8) {
+    assert!(input > 0);
+    assert!(case_name.starts_with("affixed_names_fast_"));
+    assert!(case_name.ends_with("_v2"))
+}
+
+#[test]
+fn affixes_are_added_to_names() {
+    let _ = affixed_names_fast_small_v2;
+    let _ = affixed_names_fast_slow_large_v2;
+}
+
+#[test_with_parameters(
+    case_name, group = "addition", prefix = "fast_",
+    [ input ]
+    [ 1     ]
+    #[tag(slow)]
+    [ 2     ]
+)]
+fn grouped_names(case_name: &str, input: u8) {
+    assert!(input > 0);
+    assert!(case_name.starts_with("grouped_names_addition_fast_"))
+}
+
+#[test]
+fn groups_are_added_to_names() {
+    let _ = grouped_names_addition_fast_case0;
+    let _ = grouped_names_addition_fast_slow_case1;
+}
+
+#[test_with_parameters(
+    name_template = "{fn}__{index}", case_name, start = 1,
+    [ input ]
+    [ 1     ]
+    [ 2     ]
+)]
+fn templated_names(case_name: &str, input: u8) {
+    assert_eq!(case_name, format!("templated_names__{}", input))
+}
+
+#[test_with_parameters(
+    name_template = "check_{name_column}_{fn}",
+    [ name!  , input ]
+    [ small  , 1     ]
+    [ "Big"  , 2     ]
+)]
+fn named_templates(input: u8) {
+    assert!(input > 0)
+}
+
+#[test]
+fn templates_give_whole_names() {
+    let _ = templated_names__1;
+    let _ = templated_names__2;
+    let _ = check_small_named_templates;
+    let _ = check_big_named_templates;
+}
+
+#[test_with_parameters(
+    expose_count,
+    [ input ]
+    [ 1     ]
+    [ 2     ]
+)]
+fn r#type(input: u8) {
+    assert!(input > 0)
+}
+
+#[test]
+fn raw_names_are_counted() {
+    assert_eq!(TYPE_CASE_COUNT, 2);
+}
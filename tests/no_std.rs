@@ -0,0 +1,34 @@
+#![no_std]
+
+use test_with_parameters::test_with_parameters;
+
+#[test_with_parameters(
+    no_std,
+    [ input  , expected ]
+    [ (1, 1) , 2        ]
+    #[should_panic]
+    [ (1, 2) , 4        ]
+)]
+fn cases(input: (u8, u8), expected: u8) {
+    assert_eq!(input.0 + input.1, expected)
+}
+
+#[test_with_parameters(
+    no_std, single_test,
+    [ input , expected ]
+    [ "1"   , 1        ]
+    [ "2"   , 2        ]
+)]
+fn single_tests(input: &str, expected: u8) -> Result<(), core::num::ParseIntError> {
+    assert_eq!(input.parse::<u8>()?, expected);
+    Ok(())
+}
+
+#[test_with_parameters(
+    no_std, assert = eq, setup = 10,
+    [ input , expected ]
+    [ 1     , 11       ]
+)]
+fn asserted_values(offset: u8, input: u8) -> u8 {
+    offset + input
+}
@@ -0,0 +1,56 @@
+use test_with_parameters::parameters;
+
+const CASES: [(i32, i32); 2] = parameters!(
+    [ input , expected ]
+    [ 1     , 2        ]
+    [ -1    , 0        ]
+);
+
+#[test]
+fn constant_tables() {
+    for (input, expected) in CASES {
+        assert_eq!(input + 1, expected);
+    }
+}
+
+#[test]
+fn typed_columns() {
+    let cases = parameters!(
+        [ data: &[u8] , name   ]
+        [ &[1, 2]     , "pair" ]
+        [ &[]         , "none" ]
+    );
+    assert_eq!(cases, [(&[1, 2][..], "pair"), (&[][..], "none")]);
+}
+
+#[test]
+fn single_columns() {
+    let cases = parameters!([input]['a']['b']);
+    assert_eq!(cases, [('a',), ('b',)]);
+}
+
+#[test]
+fn crossed_tables() {
+    let cases = parameters!(
+        cross
+        [ left ]
+        [ 1    ]
+        [ 2    ]
+        with
+        [ right ]
+        [ 'a'   ]
+        [ 'b'   ]
+    );
+    assert_eq!(cases, [(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b')]);
+}
+
+#[test]
+fn cfg_rows() {
+    let cases = parameters!(
+        [ input ]
+        [ 1     ]
+        #[cfg(not(test))]
+        [ 2     ]
+    );
+    assert_eq!(cases, [(1,)]);
+}
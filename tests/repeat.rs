@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use test_with_parameters::test_with_parameters;
+
+static WITHIN_LIMIT: AtomicUsize = AtomicUsize::new(0);
+static OVER_LIMIT: AtomicUsize = AtomicUsize::new(0);
+static SINGLE_TEST: AtomicUsize = AtomicUsize::new(0);
+static RESULTS: AtomicUsize = AtomicUsize::new(0);
+static UNIT: AtomicUsize = AtomicUsize::new(0);
+
+#[test_with_parameters(
+    repeat = 3,
+    [ runs          , limit ]
+    [ &WITHIN_LIMIT , 3     ]
+    #[should_panic(expected = "ran more than 2 times")]
+    [ &OVER_LIMIT   , 2     ]
+)]
+fn repeats_each_case(runs: &AtomicUsize, limit: usize) {
+    let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+    assert!(run <= limit, "ran more than {} times", limit)
+}
+
+#[test_with_parameters(
+    repeat = 2, single_test,
+    [ runs         , limit ]
+    [ &SINGLE_TEST , 3     ]
+    [ &SINGLE_TEST , 4     ]
+)]
+fn repeats_single_test(runs: &AtomicUsize, limit: usize) {
+    let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+    assert!(run <= limit, "ran more than {} times", limit)
+}
+
+#[test_with_parameters(
+    repeat = 3,
+    [ runs     , limit ]
+    [ &RESULTS , 3     ]
+)]
+fn repeats_results(runs: &AtomicUsize, limit: usize) -> Result<(), String> {
+    let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+    if run > limit {
+        return Err(format!("ran more than {} times", limit));
+    }
+    Ok(())
+}
+
+#[test_with_parameters(
+    repeat = 2,
+    [ runs  , limit ]
+    [ &UNIT , 2     ]
+)]
+#[allow(clippy::unused_unit)]
+fn repeats_unit_returns(runs: &AtomicUsize, limit: usize) -> () {
+    let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+    assert!(run <= limit, "ran more than {} times", limit)
+}
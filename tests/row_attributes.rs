@@ -0,0 +1,243 @@
+#![deny(dead_code)]
+
+use test_with_parameters::test_with_parameters;
+
+#[test_with_parameters(
+    [ input , expected ]
+    #[cfg(all())]
+    [ 1     , 2        ]
+    #[cfg(any())]
+    [ 1     , 3        ]
+    [ 2     , 4        ]
+)]
+fn cfg_rows(input: usize, expected: usize) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test_with_parameters(
+    [ input ]
+    #[cfg(any())]
+    [ 0     ]
+)]
+fn all_rows_compiled_out(input: usize) {
+    assert_ne!(input, 0)
+}
+
+fn only_called_by_compiled_out_rows(input: usize) -> usize {
+    input + 1
+}
+
+#[test_with_parameters(
+    [ input ]
+    #[cfg(any())]
+    [ 0     ]
+    #[cfg(not(test))]
+    [ 1     ]
+)]
+fn every_row_compiled_out(input: usize) {
+    assert_eq!(only_called_by_compiled_out_rows(input), 0)
+}
+
+#[test_with_parameters(
+    [ input , expected ]
+    [ 1     , 2        ]
+    #[cfg_attr(test, should_panic)]
+    [ 1     , 3        ]
+    #[cfg_attr(not(test), should_panic)]
+    [ 2     , 4        ]
+    #[cfg_attr(test, ignore)]
+    [ 3     , 0        ]
+)]
+fn cfg_attr_rows(input: usize, expected: usize) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test_with_parameters(
+    [ input , expected ]
+    [ 1     , 3        ]
+)]
+#[cfg_attr(test, should_panic)]
+fn cfg_attr_cases(input: usize, expected: usize) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test_with_parameters(
+    [ input , expected ]
+    [ 1     , 2        ]
+    #[should_panic]
+    [ 1     , 3        ]
+)]
+#[allow(clippy::unused_unit)]
+fn unit_returns_which_panic(input: usize, expected: usize) -> () {
+    assert_eq!(input * 2, expected)
+}
+
+#[test_with_parameters(
+    [ input , expected ]
+    [ 1     , 2        ]
+    skip [ 1 , 3 ]
+    skip #[should_panic]
+    [ 1     , 4        ]
+    [ 2     , 4        ]
+)]
+fn skipped_rows(input: usize, expected: usize) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test]
+fn cfg_rows_generate_only_enabled_cases() {
+    let _ = cfg_rows_case0;
+    let _ = cfg_rows_case2;
+}
+
+#[test]
+fn skipped_rows_keep_their_numbers() {
+    let _ = skipped_rows_case0;
+    let _ = skipped_rows_case3;
+}
+
+#[test_with_parameters(
+    [ millis , expected ]
+    #[timeout(1000)]
+    [ 0      , 0        ]
+    #[timeout(10)]
+    #[should_panic(expected = "did not finish within 10ms")]
+    [ 1000   , 0        ]
+    #[timeout(1000)]
+    #[should_panic(expected = "unexpected value")]
+    [ 0      , 1        ]
+)]
+fn timeouts(millis: u64, expected: u64) {
+    std::thread::sleep(std::time::Duration::from_millis(millis));
+    assert_eq!(millis, expected, "unexpected value")
+}
+
+#[test_with_parameters(
+    [ input , expected ]
+    #[note = "regression for {issue} #123"]
+    [ 1     , 1        ]
+    #[note = "first note"]
+    #[note = "second note"]
+    #[should_panic(expected = "not equal")]
+    [ 1     , 2        ]
+)]
+fn noted_rows(input: u8, expected: u8) {
+    assert_eq!(input, expected, "not equal")
+}
+
+#[test_with_parameters(
+    [ input , expected ]
+    #[timeout(1000)]
+    [ "1"   , 1        ]
+)]
+fn timeouts_with_results(input: &str, expected: u8) -> Result<(), std::num::ParseIntError> {
+    assert_eq!(input.parse::<u8>()?, expected);
+    Ok(())
+}
+
+#[test_with_parameters(
+    expose_count,
+    [ input ]
+    [ 1     ]
+    #[cfg(any())]
+    [ 2     ]
+    skip [ 3 ]
+    #[cfg(all())]
+    [ 4     ]
+)]
+fn counted_rows(input: usize) {
+    assert!(input > 0)
+}
+
+#[test_with_parameters(
+    expose_count,
+    [ input ]
+    #[cfg(any())]
+    [ 0     ]
+)]
+fn no_counted_rows(input: usize) {
+    assert_ne!(input, 0)
+}
+
+#[test]
+fn case_counts_exclude_removed_rows() {
+    assert_eq!(COUNTED_ROWS_CASE_COUNT, 2);
+    assert_eq!(NO_COUNTED_ROWS_CASE_COUNT, 0);
+}
+
+#[test_with_parameters(
+    cfg_test, expose_count,
+    [ input ]
+    [ 1     ]
+)]
+fn only_compiled_for_tests(input: usize) {
+    assert_eq!(input, ONLY_COMPILED_FOR_TESTS_CASE_COUNT)
+}
+
+#[test_with_parameters(
+    [ input   , expected ]
+    [ 1.5     , 1        ]
+    #[allow(clippy::approx_constant)]
+    [ 3.14159 , 3        ]
+)]
+fn lint_attributes(input: f64, expected: i64) {
+    assert_eq!(input as i64, expected)
+}
+
+// The feature is not one of the crate's, so it is never enabled.
+#[allow(unexpected_cfgs)]
+mod feature_gated {
+    use test_with_parameters::test_with_parameters;
+
+    #[test_with_parameters(
+        if_cfg = "disabled", expose_count,
+        [ input ]
+        [ 1     ]
+    )]
+    fn feature_gated_cases(_input: u8) {
+        panic!("This case should not have been compiled.")
+    }
+
+    #[test]
+    fn feature_gated_cases_are_not_counted() {
+        assert_eq!(FEATURE_GATED_CASES_CASE_COUNT, 0);
+    }
+}
+
+#[test_with_parameters(
+    panic_flag = fails,
+    [ input , fails , expected ]
+    [ 1     , false , 2        ]
+    [ 2     , true  , 5        ]
+)]
+fn flagged_panics(input: u8, expected: u8) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test_with_parameters(
+    panic_flag = fails, assert = eq,
+    [ input , expected , fails ]
+    [ 1     , 1        , false ]
+    [ 0     , 0        , true  ]
+)]
+fn flagged_asserted_panics(input: u8) -> u8 {
+    1 / input
+}
+
+#[test_with_parameters(
+    module, mod_attrs = [cfg(not(test)), allow(unused)],
+    [ input ]
+    [ 1     ]
+)]
+fn disabled_module(input: u8) {
+    assert_eq!(input, 0, "This case should not have been compiled.")
+}
+
+#[test_with_parameters(
+    module, mod_attrs = [cfg(test), doc = "Enabled cases."],
+    [ input ]
+    [ 1     ]
+)]
+fn enabled_module(input: u8) {
+    assert_eq!(input, 1)
+}
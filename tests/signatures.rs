@@ -0,0 +1,742 @@
+use test_with_parameters::test_with_parameters;
+
+struct Point {
+    x: u8,
+    y: u8,
+}
+
+#[test_with_parameters(
+    [ input  , expected ]
+    [ (1, 1) , 2        ]
+    [ (2, 3) , 5        ]
+)]
+fn tuple_patterns((left, right): (u8, u8), expected: u8) {
+    assert_eq!(left + right, expected)
+}
+
+#[test_with_parameters(
+    [ point                    , expected ]
+    [ Point { x: 1, y: 2 }     , 3        ]
+    [ Point { x: 0, y: 0 }     , 0        ]
+)]
+fn struct_patterns(Point { x, y }: Point, expected: u8) {
+    assert_eq!(x + y, expected)
+}
+
+#[test_with_parameters(
+    [ input: (u8, u8) , expected: u8 ]
+    [ (4, 5)          , 9            ]
+)]
+fn typed_columns_with_patterns((left, right): (u8, u8), expected: u8) {
+    assert_eq!(left + right, expected)
+}
+
+#[test_with_parameters(
+    [ input   , expected ]
+    [ "hello" , 5        ]
+    [ ""      , 0        ]
+)]
+fn lifetime_parameters<'a>(input: &'a str, expected: usize)
+where
+    'a: 'static,
+{
+    assert_eq!(input.len(), expected)
+}
+
+#[test_with_parameters(
+    [ input     , expected ]
+    [ 1u8       , "1"      ]
+    [ 'c'       , "'c'"    ]
+)]
+fn where_clauses<T>(input: T, expected: &str)
+where
+    T: std::fmt::Debug,
+{
+    assert_eq!(format!("{:?}", input), expected)
+}
+
+#[test_with_parameters(
+    [ n: u8 , expected: i128 ]
+    [ 5     , 5              ]
+    [ 255   , 255            ]
+)]
+fn narrow_literals<T: Into<i128>>(n: T, expected: i128) {
+    assert_eq!(n.into(), expected)
+}
+
+#[test_with_parameters(
+    [ n: u64        , expected: i128 ]
+    [ 5             , 5              ]
+    [ 5_000_000_000 , 5_000_000_000  ]
+)]
+fn wide_literals<T: Into<i128>>(n: T, expected: i128) {
+    assert_eq!(n.into(), expected)
+}
+
+#[test_with_parameters(
+    [ n: i64          , expected: i128  ]
+    [ 5               , 5               ]
+    [ -5_000_000_000  , -5_000_000_000  ]
+)]
+fn signed_wide_literals(n: impl Into<i128>, expected: i128) {
+    assert_eq!(n.into(), expected)
+}
+
+#[test_with_parameters(
+    single_test,
+    [ n: u64               , expected: u128 ]
+    [ 5                    , 5              ]
+    [ 18446744073709551615 , u64::MAX.into() ]
+)]
+fn wide_literals_in_single_tests(n: u64, expected: u128) {
+    assert_eq!(u128::from(n), expected)
+}
+
+#[test_with_parameters(
+    [ ty! , input  , expected ]
+    [ u8  , "7"    , 7        ]
+    [ i64 , "-7"   , -7       ]
+)]
+fn explicit_type_arguments<T>(input: &str, expected: T)
+where
+    T: std::str::FromStr + PartialEq + std::fmt::Debug,
+    T::Err: std::fmt::Debug,
+{
+    assert_eq!(input.parse::<T>().unwrap(), expected)
+}
+
+#[test_with_parameters(
+    [ const! , data      , expected ]
+    [ 2      , [1, 2]    , 3        ]
+    [ 1 + 2  , [1, 2, 3] , 6        ]
+)]
+fn const_arguments<const N: usize>(data: [u8; N], expected: u8) {
+    assert_eq!(data.iter().sum::<u8>(), expected)
+}
+
+#[test_with_parameters(
+    [ ty! , const! , expected ]
+    [ u8  , 2      , 2        ]
+    [ i64 , 4      , 4        ]
+)]
+fn type_and_const_arguments<T: Default + Copy, const N: usize>(expected: usize) {
+    assert_eq!([T::default(); N].len(), expected)
+}
+
+#[test_with_parameters(
+    types = <u16, 3>,
+    [ input   , expected ]
+    [ "7"     , 21       ]
+    [ "1000"  , 3000     ]
+)]
+fn default_type_arguments<T, const N: usize>(input: &str, expected: T)
+where
+    T: std::str::FromStr + std::ops::Mul<Output = T> + From<u8> + PartialEq + std::fmt::Debug,
+    T::Err: std::fmt::Debug,
+{
+    assert_eq!(input.parse::<T>().unwrap() * T::from(N as u8), expected)
+}
+
+#[test_with_parameters(
+    types = <u8>, single_test,
+    [ input , expected ]
+    [ "7"   , 7        ]
+)]
+fn default_type_arguments_in_single_tests<T>(input: &str, expected: T)
+where
+    T: std::str::FromStr + PartialEq + std::fmt::Debug,
+    T::Err: std::fmt::Debug,
+{
+    assert_eq!(input.parse::<T>().unwrap(), expected)
+}
+
+#[test_with_parameters(
+    types = <u8>,
+    [ ty! , input , expected ]
+    [ i64 , "-7"  , -7       ]
+)]
+fn overridden_type_arguments<T>(input: &str, expected: T)
+where
+    T: std::str::FromStr + PartialEq + std::fmt::Debug,
+    T::Err: std::fmt::Debug,
+{
+    assert_eq!(input.parse::<T>().unwrap(), expected)
+}
+
+#[test_with_parameters(
+    [ value      , expected ]
+    [ 1          , "1"      ]
+    [ "two"      , "two"    ]
+    [ '3'        , "3"      ]
+    [ 4.5        , "4.5"    ]
+)]
+fn impl_trait_arguments(value: impl std::fmt::Display, expected: &str) {
+    assert_eq!(value.to_string(), expected)
+}
+
+#[test_with_parameters(
+    [ value: impl std::fmt::Display , expected: &str ]
+    [ 1                             , "1"            ]
+    [ "two"                         , "two"          ]
+)]
+fn typed_impl_trait_arguments(value: impl std::fmt::Display, expected: &str) {
+    assert_eq!(value.to_string(), expected)
+}
+
+#[test_with_parameters(
+    setup = { vec![0] },
+    [ value: u8 , expected ]
+    [ 1         , [0, 1]   ]
+    [ 2         , [0, 2]   ]
+)]
+fn setup_fixtures(mut fixture: Vec<u8>, value: u8, expected: [u8; 2]) {
+    fixture.push(value);
+    assert_eq!(fixture, expected)
+}
+
+fn parsed_fixture(text: &str) -> Result<Vec<u8>, std::num::ParseIntError> {
+    Ok(vec![text.parse()?])
+}
+
+#[test_with_parameters(
+    setup = parsed_fixture("7")?,
+    [ value , expected ]
+    [ 1     , [7, 1]   ]
+)]
+fn fallible_setup(
+    mut fixture: Vec<u8>,
+    value: u8,
+    expected: [u8; 2],
+) -> Result<(), Box<dyn std::error::Error>> {
+    fixture.push(value);
+    assert_eq!(fixture, expected);
+    Ok(())
+}
+
+#[test_with_parameters(
+    setup = parsed_fixture("7")?, repeat = 2,
+    [ value , expected ]
+    [ 1     , [7, 1]   ]
+)]
+fn repeated_fallible_setup(
+    mut fixture: Vec<u8>,
+    value: u8,
+    expected: [u8; 2],
+) -> Result<(), std::num::ParseIntError> {
+    fixture.push(value);
+    assert_eq!(fixture, expected);
+    Ok(())
+}
+
+#[test_with_parameters(
+    setup = parsed_fixture("7")?, single_test,
+    [ value , expected ]
+    [ 1     , [7, 1]   ]
+    [ 2     , [7, 2]   ]
+)]
+fn fallible_setup_in_single_tests(
+    mut fixture: Vec<u8>,
+    value: u8,
+    expected: [u8; 2],
+) -> Result<(), std::num::ParseIntError> {
+    fixture.push(value);
+    assert_eq!(fixture, expected);
+    Ok(())
+}
+
+#[test_with_parameters(
+    setup = parsed_fixture("seven")?,
+    [ value ]
+    #[ignore = "run by `failing_setup_returns_its_error`"]
+    [ 1     ]
+)]
+fn failing_setup(_fixture: Vec<u8>, _value: u8) -> Result<(), std::num::ParseIntError> {
+    panic!("The test function was run.")
+}
+
+#[test]
+fn failing_setup_returns_its_error() {
+    assert!(failing_setup_case0().is_err());
+}
+
+#[test_with_parameters(
+    setup = vec![7],
+    [ value , expected ]
+    [ 1     , [7, 1]   ]
+)]
+fn infallible_setup(mut fixture: Vec<u8>, value: u8, expected: [u8; 2]) -> Result<(), String> {
+    fixture.push(value);
+    assert_eq!(fixture, expected);
+    Ok(())
+}
+
+#[test_with_parameters(
+    helper = renamed_helper_impl,
+    [ input , expected ]
+    [ 1     , 2        ]
+)]
+fn renamed_helper(input: u8, expected: u8) {
+    assert_eq!(input * 2, expected)
+}
+
+#[test]
+fn renamed_helper() {
+    let _ = renamed_helper_case0;
+    renamed_helper_impl(2, 4);
+}
+
+mod visible_helpers {
+    use test_with_parameters::test_with_parameters;
+
+    #[test_with_parameters(
+        helper = pub(crate) visible_helper_impl,
+        [ input , expected ]
+        [ 1     , 2        ]
+    )]
+    pub fn visible_helper(input: u8, expected: u8) {
+        assert_eq!(input * 2, expected)
+    }
+}
+
+#[test]
+fn visible_helper() {
+    visible_helpers::visible_helper_impl(2, 4);
+}
+
+#[test_with_parameters(
+    [ a , b , c , expected ]
+    [ 1 , 2 , 3 , 6        ]
+    [ 4 , 5 , 6 , 15       ]
+)]
+fn three_parameters(a: u8, b: u8, c: u8, expected: u8) {
+    assert_eq!(a + b + c, expected)
+}
+
+#[test_with_parameters(
+    [ a   , b    , c    , d    , e         ]
+    [ 1u8 , 2u16 , 3u32 , 4u64 , "1 2 3 4" ]
+    [ 0   , 0    , 0    , 0    , "0 0 0 0" ]
+)]
+fn many_parameters(a: u8, b: u16, c: u32, d: u64, e: &str) {
+    assert_eq!(format!("{} {} {} {}", a, b, c, d), e)
+}
+
+#[test_with_parameters(
+    [ ty! ]
+    [ u8  ]
+    [ i64 ]
+)]
+fn no_value_parameters<T: Default + PartialEq + std::fmt::Debug>() {
+    assert_eq!(T::default(), T::default())
+}
+
+#[test_with_parameters(
+    inline, setup = { vec![1] },
+    [ input         , expected: usize ]
+    [ (1, 1)        , 2               ]
+    #[should_panic(expected = "not equal")]
+    [ (2, 2)        , 5               ]
+)]
+fn inlined(mut items: Vec<usize>, (left, right): (usize, usize), expected: usize) {
+    items.push(left + right);
+    assert_eq!(items[1], expected, "not equal")
+}
+
+#[test_with_parameters(
+    inline,
+    [ input , expected ]
+    [ "1"   , 1        ]
+)]
+fn inlined_results(input: &str, expected: u8) -> Result<(), std::num::ParseIntError> {
+    assert_eq!(input.parse::<u8>()?, expected);
+    Ok(())
+}
+
+#[test]
+fn inlined() {
+    let _ = inlined_case0;
+    let _ = inlined_case1;
+}
+
+static FIXTURE: [u8; 3] = [1, 2, 3];
+const GREETING: &str = "hello";
+
+#[test_with_parameters(
+    [ data      , expected ]
+    [ &FIXTURE  , 6        ]
+    [ &FIXTURE  , 6u16     ]
+    [ &[4, 5]   , 9        ]
+)]
+fn static_references(data: &[u8], expected: u16) {
+    assert_eq!(data.iter().map(|&x| u16::from(x)).sum::<u16>(), expected)
+}
+
+#[test_with_parameters(
+    [ data: &'static [u8; 3] , greeting: &str , expected ]
+    [ &FIXTURE               , GREETING       , 3        ]
+    [ &FIXTURE               , &GREETING[1..] , 3        ]
+)]
+fn typed_static_references(data: &'static [u8; 3], greeting: &str, expected: usize) {
+    assert!(greeting.ends_with("llo"));
+    assert_eq!(data.len(), expected)
+}
+
+#[test_with_parameters(
+    single_test,
+    [ data: &[u8] , expected ]
+    [ &FIXTURE    , 6        ]
+    [ &[4, 5]     , 9        ]
+)]
+fn static_references_in_single_tests(data: &[u8], expected: u16) {
+    assert_eq!(data.iter().map(|&x| u16::from(x)).sum::<u16>(), expected)
+}
+
+#[test_with_parameters(
+    setup = { Vec::new() },
+    [ value , expected ]
+    [ 1     , [1]      ]
+    [ 2     , [2]      ]
+)]
+fn mutable_fixtures(fixture: &mut Vec<u8>, value: u8, expected: [u8; 1]) {
+    fixture.push(value);
+    assert_eq!(*fixture, expected)
+}
+
+#[test_with_parameters(
+    setup = String::from("fixture"), case_name,
+    [ len ]
+    [ 7   ]
+)]
+fn borrowed_fixtures(case_name: &str, fixture: &str, len: usize) {
+    assert_eq!(case_name, "borrowed_fixtures_case0");
+    assert_eq!(fixture.len(), len)
+}
+
+#[test_with_parameters(
+    setup = "fixture",
+    [ len ]
+    [ 7   ]
+)]
+fn static_fixtures(fixture: &'static str, len: usize) {
+    assert_eq!(fixture.len(), len)
+}
+
+#[test_with_parameters(
+    assert = eq,
+    [ input  , expected ]
+    [ (1, 1) , 2        ]
+    [ (2, 3) , 5        ]
+    #[should_panic(expected = "assertion `left == right` failed")]
+    [ (2, 2) , 5        ]
+)]
+fn asserted_values(input: (u8, u8)) -> u8 {
+    input.0 + input.1
+}
+
+#[test_with_parameters(
+    assert = eq,
+    [ input , expected             ]
+    [ "1"   , Ok(1)                ]
+    [ "256" , "1000".parse::<u8>() ]
+)]
+fn asserted_results(input: &str) -> Result<u8, std::num::ParseIntError> {
+    input.parse()
+}
+
+#[test_with_parameters(
+    expr = { a + b },
+    [ a , b   , expected ]
+    [ 1 , 2   , 3        ]
+    [ 2 , 2u8 , 4        ]
+    #[should_panic(expected = "assertion `left == right` failed")]
+    [ 2 , 2   , 5        ]
+)]
+fn expressions() {}
+
+#[test_with_parameters(
+    expr = a && !b, assert = matches,
+    [ tuple!(a, b)  , expected ]
+    [ true  , false , true     ]
+    [ false , false , false    ]
+)]
+fn matched_expressions() {}
+
+#[test_with_parameters(
+    [ input   , check!                                          ]
+    [ "a b"   , |words| assert_eq!(words, ["a", "b"])           ]
+    [ ""      , |words: Vec<&str>| assert!(words.is_empty())    ]
+    #[should_panic(expected = "too many words")]
+    [ "a b c" , |words| { assert!(words.len() < 3, "too many words") } ]
+)]
+fn checked_values(input: &str) -> Vec<&str> {
+    input.split_whitespace().collect()
+}
+
+#[test_with_parameters(
+    assert = err,
+    [ input , expected_err                ]
+    [ "1"   , None                        ]
+    [ "one" , "invalid digit"             ]
+    [ "256" , String::from("too large")   ]
+    [ ""    , ""                          ]
+    #[should_panic(expected = "but the test function returned `Ok`")]
+    [ "2"   , "invalid digit"             ]
+    #[should_panic(expected = "but the error was `invalid digit found in string`")]
+    [ "two" , "too large"                 ]
+    #[should_panic(expected = "returned the error `cannot parse integer from empty string`")]
+    [ ""    , None                        ]
+)]
+fn asserted_errors(input: &str) -> Result<u8, std::num::ParseIntError> {
+    input.parse()
+}
+
+#[test_with_parameters(
+    assert = no_panic,
+    [ input , divisor ]
+    [ 7     , 2       ]
+    [ 0     , 1       ]
+    #[should_panic(expected = "Case `unpanicking_values_case2` panicked, but `assert = no_panic` expects it not to.")]
+    [ 1     , 0       ]
+)]
+fn unpanicking_values(input: u8, divisor: u8) -> u8 {
+    input / divisor
+}
+
+fn naive_sum(values: Vec<u32>, times: u32) -> u32 {
+    (0..times).map(|_| values.iter().sum::<u32>()).sum()
+}
+
+#[test_with_parameters(
+    reference = naive_sum,
+    [ values       , times ]
+    [ vec![]       , 3     ]
+    [ vec![1, 2]   , 1     ]
+    [ vec![4, 5, 6], 2     ]
+    #[should_panic(expected = "assertion `left == right` failed")]
+    [ vec![7]      , 0     ]
+)]
+fn reference_values(values: Vec<u32>, times: u32) -> u32 {
+    values.into_iter().sum::<u32>() * times.max(1)
+}
+
+#[test_with_parameters(
+    [ input , offset , scale: u8 , expected ]
+    [ 1     , 1      , 10        , 2        ]
+    [ 2     , 3      , 10        , 5        ]
+)]
+fn cfg_parameters(input: u8, #[cfg(test)] offset: u8, #[cfg(not(test))] scale: u8, expected: u8) {
+    assert_eq!(input + offset, expected)
+}
+
+#[test_with_parameters(
+    inline,
+    [ input , removed: &str , expected ]
+    [ 1     , "removed"     , 1        ]
+)]
+fn inlined_cfg_parameters(input: u8, #[cfg(not(test))] removed: &str, expected: u8) {
+    assert_eq!(input, expected)
+}
+
+thread_local! {
+    static TEARDOWNS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+fn count_teardown() {
+    TEARDOWNS.with(|count| count.set(count.get() + 1));
+}
+
+#[test_with_parameters(
+    single_test, teardown = count_teardown(),
+    [ previous ]
+    [ 0        ]
+    [ 1        ]
+    [ 2        ]
+)]
+fn teardowns(previous: usize) {
+    assert_eq!(TEARDOWNS.with(std::cell::Cell::get), previous)
+}
+
+#[test_with_parameters(
+    teardown = { count_teardown() },
+    [ input ]
+    #[should_panic(expected = "failed")]
+    [ 1     ]
+)]
+fn panicking_teardowns(input: u8) {
+    assert_eq!(input, 0, "failed")
+}
+
+#[test]
+fn teardowns_run_after_panics() {
+    let result = std::panic::catch_unwind(panicking_teardowns_case0);
+    assert!(result.is_err());
+    assert_eq!(TEARDOWNS.with(std::cell::Cell::get), 1)
+}
+
+#[test_with_parameters(
+    strict, setup = 1,
+    [ input , point  , expected ]
+    [ 1     , (1, 2) , 5        ]
+)]
+fn strict_names(offset: u8, mut input: u8, (x, y): (u8, u8), expected: u8) {
+    input += offset;
+    assert_eq!(input + x + y, expected)
+}
+
+#[test_with_parameters(
+    assert = eq, expected = 2,
+    [ a , b , sum , product ]
+    [ 2 , 3 , 5   , 6       ]
+    [ 0 , 7 , 7   , 0       ]
+)]
+fn asserted_fields(a: u32, b: u32) -> (u32, u32) {
+    (a + b, a * b)
+}
+
+#[test_with_parameters(
+    assert = matches, expected = 3,
+    [ input , first   , second    , rest ]
+    [ "a b" , Some(_) , Some("b") , None ]
+    [ ""    , None    , None      , None ]
+)]
+fn matched_fields(input: &str) -> (Option<&str>, Option<&str>, Option<&str>) {
+    let mut words = input.split_whitespace();
+    (words.next(), words.next(), words.next())
+}
+
+struct Context {
+    base: u8,
+}
+
+impl Context {
+    fn new() -> Self {
+        Context { base: 10 }
+    }
+}
+
+#[test_with_parameters(
+    with_context = Context::new,
+    [ input , expected ]
+    [ 1     , 11       ]
+    [ 2     , 12       ]
+)]
+fn contexts(ctx: &Context, input: u8, expected: u8) {
+    assert_eq!(ctx.base + input, expected)
+}
+
+struct Calculator;
+
+impl Calculator {
+    fn check_add(left: u8, right: u8, expected: u8) {
+        assert_eq!(left + right, expected)
+    }
+}
+
+#[test_with_parameters(
+    call = Calculator::check_add, no_helper,
+    [ left , right , expected ]
+    [ 1    , 2     , 3        ]
+    [ 4    , 5     , 9        ]
+)]
+fn external_calls(left: u8, right: u8, expected: u8) {}
+
+#[test_with_parameters(
+    module, call = Calculator::check_add,
+    [ left , right , expected ]
+    [ 2    , 2     , 4        ]
+)]
+fn external_calls_in_modules(left: u8, right: u8, expected: u8) {
+    Calculator::check_add(left, right, expected)
+}
+
+#[test]
+fn external_calls_keep_helpers() {
+    external_calls_in_modules(1, 1, 2);
+}
+
+fn base() -> u8 {
+    10
+}
+
+fn scale() -> Vec<u8> {
+    vec![2]
+}
+
+#[test_with_parameters(
+    fixtures = [base, scale],
+    [ input , expected ]
+    [ 1     , 22       ]
+    [ 2     , 24       ]
+)]
+fn fixture_functions(base: u8, scale: Vec<u8>, input: u8, expected: u8) {
+    assert_eq!((base + input) * scale[0], expected)
+}
+
+#[test_with_parameters(
+    setup = 1, fixtures = [base], single_test, case_name,
+    [ input , expected ]
+    [ 1     , 12       ]
+    [ 2     , 13       ]
+)]
+fn fixture_functions_after_setup(case_name: &str, offset: u8, base: u8, input: u8, expected: u8) {
+    assert!(case_name.starts_with("fixture_functions_after_setup"));
+    assert_eq!(offset + base + input, expected)
+}
+
+static RUNNING: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[test_with_parameters(
+    serial,
+    [ input ]
+    [ 1     ]
+    [ 2     ]
+    #[should_panic(expected = "poisoned")]
+    [ 3     ]
+)]
+#[test_with_parameters(
+    serial,
+    [ input ]
+    [ 4     ]
+    [ 5     ]
+)]
+fn serial_cases(input: u64) {
+    use std::sync::atomic::Ordering;
+
+    assert_eq!(RUNNING.fetch_add(1, Ordering::SeqCst), 0);
+    std::thread::sleep(std::time::Duration::from_millis(input * 5));
+    RUNNING.fetch_sub(1, Ordering::SeqCst);
+    assert_ne!(input, 3, "poisoned")
+}
+
+#[test_with_parameters(
+    serial,
+    [ input ]
+    [ 1     ]
+    [ 2     ]
+)]
+fn r#loop(input: u8) {
+    assert!(input > 0)
+}
+
+#[test_with_parameters(
+    const_check, assert = eq,
+    [ input , doubled   ]
+    [ 1     , 2         ]
+    [ 4     , input * 2 ]
+    #[should_panic]
+    [ 5     , 11        ]
+)]
+const fn const_checked(input: u32) -> u32 {
+    input * 2
+}
+
+#[test_with_parameters(
+    const_check, helper = bounded_impl,
+    [ value , limit ]
+    [ 1     , 2     ]
+    #[ignore]
+    [ 3     , 2     ]
+)]
+const fn bounded(value: u8, limit: u8) {
+    assert!(value < limit)
+}